@@ -13,13 +13,70 @@
 
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype, Env, Address, Symbol, token,
+    contract, contractclient, contracterror, contractimpl, contracttype, Env, Address, Bytes, BytesN,
+    Map, Symbol, Vec, token,
 };
 
 
 
 
 
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum EscrowError {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    InvalidAmount = 3,
+    EscrowExists = 4,
+    EscrowNotFound = 5,
+    AlreadyReleased = 6,
+    AlreadyLiquidated = 7,
+    PositionHealthy = 8,
+    NotLiquidatable = 9,
+    CollateralRatioTooLow = 10,
+    InsufficientCollateral = 11,
+    TokenNotAccepted = 12,
+    OracleNotConfigured = 13,
+    OraclePriceUnavailable = 14,
+    StaleOraclePrice = 15,
+    InvalidOraclePrice = 16,
+    LoanTokenNotConfigured = 17,
+    ThresholdNotSet = 18,
+    InvalidConfig = 19,
+    DealNotFound = 20,
+    InvalidDealState = 21,
+    NoArbiter = 22,
+    InvalidSplit = 23,
+    TimeoutNotReached = 24,
+    MilestoneEscrowNotFound = 25,
+    MilestoneNotFound = 26,
+    InvalidMilestoneState = 27,
+    InvalidMilestones = 28,
+    MilestoneOutOfOrder = 29,
+    DeadlinePassed = 30,
+    DeadlineNotReached = 31,
+    HtlcNotFound = 32,
+    InvalidHtlcState = 33,
+    HtlcExpired = 34,
+    HtlcNotExpired = 35,
+    InvalidPreimage = 36,
+    InvalidTimelock = 37,
+    AuctionNotFound = 38,
+    AuctionClosed = 39,
+    LotNotFound = 40,
+    AuctionNotConfigured = 41,
+    LoanAlreadyConfigured = 42,
+    LoanNotConfigured = 43,
+    Unauthorized = 44,
+    NoPendingAdmin = 45,
+    HandoverExpired = 46,
+    NotSigner = 47,
+    AlreadyApproved = 48,
+    InsufficientApprovals = 49,
+    MathOverflow = 50,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct EscrowData {
@@ -33,6 +90,234 @@ pub struct EscrowData {
     pub due_date: u64,
     pub is_locked: bool,
     pub is_liquidated: bool,
+    pub basket: Map<Address, i128>,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EscrowStatus {
+    Locked,
+    Released,
+    Liquidated,
+}
+
+// One page of an index listing. Each call scans at most `limit` index
+// entries, so a page filtered by status may hold fewer escrows (or none) while
+// more remain. Pass `next_cursor` back to continue; `None` means the index is
+// exhausted.
+#[contracttype]
+#[derive(Clone)]
+pub struct EscrowPage {
+    pub escrows: Vec<EscrowData>,
+    pub next_cursor: Option<u32>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+// SEP-40 style price feed; prices for every asset share the feed's decimals.
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    fn lastprice(env: Env, asset: Address) -> Option<PriceData>;
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct OracleConfig {
+    pub oracle: Address,
+    pub max_price_age: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct LiquidationConfig {
+    pub grace_period: u64,
+    pub liquidator_bonus_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DealStatus {
+    Funded,
+    Delivered,
+    Disputed,
+    Completed,
+    Resolved,
+    Refunded,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Deal {
+    pub deal_id: u64,
+    pub buyer: Address,
+    pub seller: Address,
+    pub arbiter: Option<Address>,
+    pub token: Address,
+    pub amount: i128,
+    pub created_at: u64,
+    pub delivered_at: u64,
+    pub release_timeout: u64,
+    pub status: DealStatus,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MilestoneStatus {
+    Pending,
+    Claimed,
+    Disputed,
+    Released,
+    Refunded,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Milestone {
+    pub amount: i128,
+    pub deadline: u64,
+    pub status: MilestoneStatus,
+    pub claimed_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct MilestoneEscrow {
+    pub escrow_id: u64,
+    pub funder: Address,
+    pub payee: Address,
+    pub arbiter: Option<Address>,
+    pub token: Address,
+    pub milestones: Vec<Milestone>,
+    pub created_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HtlcStatus {
+    Locked,
+    Claimed,
+    Refunded,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Htlc {
+    pub htlc_id: u64,
+    pub sender: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub hashlock: BytesN<32>,
+    pub timelock: u64,
+    pub preimage: Option<Bytes>,
+    pub status: HtlcStatus,
+}
+
+// Descending-price auction parameters for one collateral token. Prices start
+// at `start_premium_bps` of the oracle value of the lot in the loan token and
+// fall linearly to `floor_bps` over `duration` seconds. `penalty_bps` is this
+// token's share of the penalty added on top of the debt the auction has to
+// recover, weighted by the value of its lot.
+#[contracttype]
+#[derive(Clone)]
+pub struct AuctionConfig {
+    pub start_premium_bps: u32,
+    pub floor_bps: u32,
+    pub duration: u64,
+    pub penalty_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct AuctionLot {
+    pub amount: i128,
+    pub start_price: i128,
+    pub floor_price: i128,
+    pub duration: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Auction {
+    pub loan_id: u64,
+    pub started_at: u64,
+    pub debt_remaining: i128,
+    pub proceeds: i128,
+    pub lots: Map<Address, AuctionLot>,
+    pub is_active: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InterestMode {
+    Simple,
+    Compound,
+}
+
+// Repayment state for a loan serviced through the escrow. `rate_bps` is an
+// annual rate. Simple interest accrues on the unpaid principal; compound
+// interest capitalises once per whole day. Repayments settle accrued interest
+// before principal.
+#[contracttype]
+#[derive(Clone)]
+pub struct LoanAccount {
+    pub loan_id: u64,
+    pub rate_bps: u32,
+    pub mode: InterestMode,
+    pub principal: i128,
+    pub interest: i128,
+    // Simple-interest accrual not yet large enough to add a whole unit to
+    // `interest`, in units of 1 / (BPS_DENOMINATOR * DAYS_PER_YEAR * SECONDS_PER_DAY).
+    pub interest_remainder: i128,
+    pub accrued_at: u64,
+    pub total_repaid: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct PendingAdmin {
+    pub admin: Address,
+    pub expires_at: u64,
+}
+
+// When configured, `threshold` of `signers` must approve privileged actions
+// instead of the single admin key. An approval only counts for
+// APPROVAL_WINDOW seconds after it is given.
+#[contracttype]
+#[derive(Clone)]
+pub struct SignerSet {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum AdminAction {
+    Liquidate(u64),
+    ProposeAdmin(Address, u64),
+    SetSignerSet(Vec<Address>, u32),
+    SetKeeper(Address),
+    SetMinCollateralRatio(u32),
+    SetOracle(Address, u64),
+    SetLoanToken(Address),
+    SetLiquidationThreshold(Address, u32),
+    SetLiquidationConfig(u64, u32),
+    SetCollateralHaircut(Address, u32),
+    SetAuctionConfig(Address, u32, u32, u64, u32),
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct SolvencyReport {
+    pub token: Address,
+    pub recorded_total: i128,
+    pub contract_balance: i128,
+    pub is_solvent: bool,
 }
 
 #[contracttype]
@@ -40,8 +325,567 @@ pub enum EscrowKey {
     Escrow(u64),      
     Admin,            
     TotalLocked,      
+    MinCollateralRatio,
+    Oracle,
+    LoanToken,
+    LiquidationThreshold(Address),
+    LiquidationConfig,
+    CollateralHaircut(Address),
+    Deal(u64),
+    DealCounter,
+    MilestoneEscrow(u64),
+    MilestoneCounter,
+    TotalLockedByToken(Address),
+    Htlc(u64),
+    HtlcCounter,
+    AuctionConfig(Address),
+    Auction(u64),
+    BorrowerLoans(Address),
+    LenderLoans(Address),
+    LoanAccount(u64),
+    Keeper,
+    PendingAdmin,
+    SignerSet,
+    Approvals(AdminAction),
+}
+
+const BPS_DENOMINATOR: i128 = 10_000;
+const DEFAULT_MIN_COLLATERAL_RATIO_BPS: u32 = 15_000;
+const DEFAULT_GRACE_PERIOD: u64 = 86_400;
+const DEFAULT_LIQUIDATOR_BONUS_BPS: u32 = 500;
+const MAX_PAGE_SIZE: u32 = 50;
+const APPROVAL_WINDOW: u64 = 7 * 86_400;
+const MAX_INTEREST_RATE_BPS: u32 = 10_000;
+const MAX_LIQUIDATION_PENALTY_BPS: u32 = 2_000;
+const AUCTION_FLOOR_PERIOD: u64 = 86_400;
+const SECONDS_PER_DAY: u64 = 86_400;
+const MILESTONE_REVIEW_PERIOD: u64 = 7 * SECONDS_PER_DAY;
+const DAYS_PER_YEAR: i128 = 365;
+const RATE_SCALE: i128 = 1_000_000_000_000;
+const DAY_IN_LEDGERS: u32 = 17_280;
+const TTL_THRESHOLD: u32 = 30 * DAY_IN_LEDGERS;
+const TTL_EXTEND_TO: u32 = 180 * DAY_IN_LEDGERS;
+
+// Keeps the per-token total and the legacy all-token aggregate in step.
+fn adjust_total_locked(env: &Env, token: &Address, delta: i128) {
+    let total: i128 = env.storage().persistent()
+        .get(&EscrowKey::TotalLocked)
+        .unwrap_or(0);
+    env.storage().persistent().set(&EscrowKey::TotalLocked, &(total + delta));
+
+    let key = EscrowKey::TotalLockedByToken(token.clone());
+    let token_total: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+    env.storage().persistent().set(&key, &(token_total + delta));
+
+    extend_persistent_ttl(env, &EscrowKey::TotalLocked);
+    extend_persistent_ttl(env, &key);
+}
+
+fn read_admin(env: &Env) -> Result<Address, EscrowError> {
+    env.storage().persistent()
+        .get(&EscrowKey::Admin)
+        .ok_or(EscrowError::NotInitialized)
+}
+
+// Gates a privileged action on the admin's signature, or on enough signer
+// approvals when a signer set is configured. Approvals are consumed so each
+// one authorises a single execution.
+// Approvals for `action` still inside APPROVAL_WINDOW, keyed by signer with
+// the time each was given.
+fn live_approvals(env: &Env, action: AdminAction) -> Map<Address, u64> {
+    let approvals: Map<Address, u64> = env.storage().persistent()
+        .get(&EscrowKey::Approvals(action))
+        .unwrap_or(Map::new(env));
+
+    let now = env.ledger().timestamp();
+    let mut live = Map::new(env);
+    for (signer, approved_at) in approvals.iter() {
+        if now <= approved_at.saturating_add(APPROVAL_WINDOW) {
+            live.set(signer, approved_at);
+        }
+    }
+    live
+}
+
+fn authorize_admin_action(env: &Env, action: AdminAction) -> Result<(), EscrowError> {
+    let signer_set: SignerSet = match env.storage().persistent().get(&EscrowKey::SignerSet) {
+        Some(set) => set,
+        None => {
+            read_admin(env)?.require_auth();
+            return Ok(());
+        }
+    };
+
+    let key = EscrowKey::Approvals(action.clone());
+    let approvals = live_approvals(env, action);
+
+    // Approvals from signers that have since been removed no longer count.
+    let count = approvals.keys().iter().filter(|a| signer_set.signers.contains(a)).count() as u32;
+    if count < signer_set.threshold {
+        return Err(EscrowError::InsufficientApprovals);
+    }
+
+    env.storage().persistent().remove(&key);
+    Ok(())
+}
+
+fn read_escrow(env: &Env, loan_id: u64) -> Result<EscrowData, EscrowError> {
+    let escrow = env.storage().persistent()
+        .get(&EscrowKey::Escrow(loan_id))
+        .ok_or(EscrowError::EscrowNotFound)?;
+    extend_escrow_ttl(env, loan_id);
+    Ok(escrow)
+}
+
+fn extend_persistent_ttl(env: &Env, key: &EscrowKey) {
+    if env.storage().persistent().has(key) {
+        env.storage().persistent().extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+}
+
+// Keeps the contract instance, an escrow and its loan account from being
+// archived while the escrow is in use.
+fn extend_escrow_ttl(env: &Env, loan_id: u64) {
+    env.storage().instance().extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+    extend_persistent_ttl(env, &EscrowKey::Escrow(loan_id));
+    extend_persistent_ttl(env, &EscrowKey::LoanAccount(loan_id));
+}
+
+// Keeps the contract instance and a deal, milestone escrow, HTLC or auction
+// from being archived while it is in use. Called on every write.
+fn extend_agreement_ttl(env: &Env, key: &EscrowKey) {
+    env.storage().instance().extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+    extend_persistent_ttl(env, key);
+}
+
+// Everything a live loan reads besides its own escrow and account: the
+// per-token settings of its basket, the party indexes and any auction.
+fn extend_loan_refs_ttl(env: &Env, escrow: &EscrowData) {
+    for token in collateral_basket(escrow).keys().iter() {
+        extend_persistent_ttl(env, &EscrowKey::LiquidationThreshold(token.clone()));
+        extend_persistent_ttl(env, &EscrowKey::CollateralHaircut(token.clone()));
+        extend_persistent_ttl(env, &EscrowKey::AuctionConfig(token.clone()));
+        extend_persistent_ttl(env, &EscrowKey::TotalLockedByToken(token));
+    }
+    extend_persistent_ttl(env, &EscrowKey::BorrowerLoans(escrow.borrower.clone()));
+    extend_persistent_ttl(env, &EscrowKey::LenderLoans(escrow.lender.clone()));
+    extend_persistent_ttl(env, &EscrowKey::Auction(escrow.loan_id));
+}
+
+// Contract-wide settings live in persistent storage too; keepers refresh
+// them alongside the escrows they bump.
+fn extend_config_ttl(env: &Env) {
+    for key in [
+        EscrowKey::Admin,
+        EscrowKey::Keeper,
+        EscrowKey::PendingAdmin,
+        EscrowKey::SignerSet,
+        EscrowKey::TotalLocked,
+        EscrowKey::MinCollateralRatio,
+        EscrowKey::Oracle,
+        EscrowKey::LoanToken,
+        EscrowKey::LiquidationConfig,
+        EscrowKey::DealCounter,
+        EscrowKey::MilestoneCounter,
+        EscrowKey::HtlcCounter,
+    ] {
+        extend_persistent_ttl(env, &key);
+    }
+}
+
+// Only a locked, unliquidated escrow can be topped up, withdrawn from,
+// released or liquidated.
+fn ensure_active(escrow: &EscrowData) -> Result<(), EscrowError> {
+    if escrow.is_liquidated {
+        return Err(EscrowError::AlreadyLiquidated);
+    }
+    if !escrow.is_locked {
+        return Err(EscrowError::AlreadyReleased);
+    }
+    Ok(())
+}
+
+fn read_loan_token(env: &Env) -> Result<Address, EscrowError> {
+    env.storage().persistent()
+        .get(&EscrowKey::LoanToken)
+        .ok_or(EscrowError::LoanTokenNotConfigured)
+}
+
+fn escrow_status(escrow: &EscrowData) -> EscrowStatus {
+    if escrow.is_liquidated {
+        EscrowStatus::Liquidated
+    } else if escrow.is_locked {
+        EscrowStatus::Locked
+    } else {
+        EscrowStatus::Released
+    }
+}
+
+fn require_admin_or_keeper(env: &Env, caller: &Address) -> Result<(), EscrowError> {
+    caller.require_auth();
+
+    let admin = read_admin(env)?;
+    let keeper: Option<Address> = env.storage().persistent().get(&EscrowKey::Keeper);
+    if *caller != admin && Some(caller.clone()) != keeper {
+        return Err(EscrowError::Unauthorized);
+    }
+    Ok(())
+}
+
+fn index_loan(env: &Env, key: EscrowKey, loan_id: u64) {
+    let mut loan_ids: Vec<u64> = env.storage().persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    loan_ids.push_back(loan_id);
+    env.storage().persistent().set(&key, &loan_ids);
+    extend_persistent_ttl(env, &key);
+}
+
+fn list_indexed(
+    env: &Env,
+    key: EscrowKey,
+    status: Option<EscrowStatus>,
+    cursor: u32,
+    limit: u32,
+) -> EscrowPage {
+    let loan_ids: Vec<u64> = env.storage().persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    let limit = limit.clamp(1, MAX_PAGE_SIZE);
+
+    // Plain reads: a listing is a view and must not extend TTLs.
+    let mut escrows = Vec::new(env);
+    let end = cursor.saturating_add(limit).min(loan_ids.len());
+    let mut index = cursor;
+    while index < end {
+        let escrow: Option<EscrowData> = env.storage().persistent()
+            .get(&EscrowKey::Escrow(loan_ids.get_unchecked(index)));
+        if let Some(escrow) = escrow {
+            if status.is_none_or(|s| s == escrow_status(&escrow)) {
+                escrows.push_back(escrow);
+            }
+        }
+        index += 1;
+    }
+
+    let next_cursor = if index < loan_ids.len() { Some(index) } else { None };
+    EscrowPage { escrows, next_cursor }
+}
+
+fn min_collateral_ratio(env: &Env) -> u32 {
+    env.storage().persistent()
+        .get(&EscrowKey::MinCollateralRatio)
+        .unwrap_or(DEFAULT_MIN_COLLATERAL_RATIO_BPS)
+}
+
+fn liquidation_config(env: &Env) -> LiquidationConfig {
+    env.storage().persistent()
+        .get(&EscrowKey::LiquidationConfig)
+        .unwrap_or(LiquidationConfig {
+            grace_period: DEFAULT_GRACE_PERIOD,
+            liquidator_bonus_bps: DEFAULT_LIQUIDATOR_BONUS_BPS,
+        })
+}
+
+fn oracle_price(env: &Env, config: &OracleConfig, asset: &Address) -> Result<i128, EscrowError> {
+    let data = PriceOracleClient::new(env, &config.oracle)
+        .lastprice(asset)
+        .ok_or(EscrowError::OraclePriceUnavailable)?;
+
+    if data.timestamp.saturating_add(config.max_price_age) < env.ledger().timestamp() {
+        return Err(EscrowError::StaleOraclePrice);
+    }
+    if data.price <= 0 {
+        return Err(EscrowError::InvalidOraclePrice);
+    }
+
+    Ok(data.price)
+}
+
+fn publish_milestone_event(env: &Env, name: &str, escrow_id: u64, index: u32, milestone: &Milestone) {
+    env.events().publish(
+        (Symbol::new(env, name), escrow_id),
+        (index, milestone.amount, milestone.status),
+    );
+}
+
+fn lot_price(lot: &AuctionLot, elapsed: u64) -> i128 {
+    if elapsed >= lot.duration {
+        return lot.floor_price;
+    }
+    lot.start_price - (lot.start_price - lot.floor_price) * elapsed as i128 / lot.duration as i128
+}
+
+// True once any token in the escrow's basket has an auction config, in which
+// case liquidation must go through `start_auction`.
+fn auction_configured(env: &Env, escrow: &EscrowData) -> bool {
+    collateral_basket(escrow).keys().iter()
+        .any(|token| env.storage().persistent().has(&EscrowKey::AuctionConfig(token)))
+}
+
+// Moves a liquidated escrow's collateral into a Dutch auction instead of
+// handing it to the lender. The collateral stays in the contract (and in the
+// locked totals) until each lot is bought. Every basket token needs its own
+// auction config; a partly configured basket cannot be liquidated. The
+// penalty is the value-weighted blend of each lot's configured penalty, so it
+// does not depend on which token was locked first.
+fn start_auction(env: &Env, escrow: &EscrowData, initiator: &Address) -> Result<(), EscrowError> {
+    let oracle: OracleConfig = env.storage().persistent()
+        .get(&EscrowKey::Oracle)
+        .ok_or(EscrowError::OracleNotConfigured)?;
+    let loan_price = oracle_price(env, &oracle, &read_loan_token(env)?)?;
+
+    let mut lots = Map::new(env);
+    let mut total_value: i128 = 0;
+    let mut weighted_penalty: i128 = 0;
+    for (token, amount) in collateral_basket(escrow).iter() {
+        let config: AuctionConfig = env.storage().persistent()
+            .get(&EscrowKey::AuctionConfig(token.clone()))
+            .ok_or(EscrowError::AuctionNotConfigured)?;
+
+        let value = amount * oracle_price(env, &oracle, &token)? / loan_price;
+        total_value += value;
+        weighted_penalty += value * config.penalty_bps as i128;
+        lots.set(token, AuctionLot {
+            amount,
+            start_price: value * config.start_premium_bps as i128 / BPS_DENOMINATOR,
+            floor_price: value * config.floor_bps as i128 / BPS_DENOMINATOR,
+            duration: config.duration,
+        });
+    }
+
+    let debt = outstanding_debt(env, escrow)?;
+    let penalty = if total_value > 0 {
+        debt * weighted_penalty / (total_value * BPS_DENOMINATOR)
+    } else {
+        0
+    };
+    let debt_remaining = debt + penalty;
+
+    let auction = Auction {
+        loan_id: escrow.loan_id,
+        started_at: env.ledger().timestamp(),
+        debt_remaining,
+        proceeds: 0,
+        lots,
+        is_active: true,
+    };
+    env.storage().persistent().set(&EscrowKey::Auction(escrow.loan_id), &auction);
+    extend_agreement_ttl(env, &EscrowKey::Auction(escrow.loan_id));
+
+    let mut updated_escrow = escrow.clone();
+    updated_escrow.is_locked = false;
+    updated_escrow.is_liquidated = true;
+    env.storage().persistent().set(&EscrowKey::Escrow(escrow.loan_id), &updated_escrow);
+
+    env.events().publish(
+        (Symbol::new(env, "AuctionStarted"), initiator.clone()),
+        (escrow.loan_id, escrow.borrower.clone(), debt_remaining),
+    );
+
+    Ok(())
+}
+
+// Closes an auction whose lots are all gone. A liquidated loan is settled by
+// its auction, so any serviced loan account is written down to zero and
+// whatever debt the proceeds did not cover is reported as the shortfall.
+fn settle_auction(env: &Env, auction: &mut Auction) {
+    auction.is_active = false;
+
+    let key = EscrowKey::LoanAccount(auction.loan_id);
+    if let Some(mut account) = env.storage().persistent().get::<_, LoanAccount>(&key) {
+        account.principal = 0;
+        account.interest = 0;
+        account.interest_remainder = 0;
+        account.accrued_at = env.ledger().timestamp();
+        env.storage().persistent().set(&key, &account);
+        extend_persistent_ttl(env, &key);
+    }
+
+    env.events().publish(
+        (Symbol::new(env, "AuctionSettled"),),
+        (auction.loan_id, auction.proceeds, auction.debt_remaining),
+    );
+}
+
+// `a * b / d` for non-negative `a` and `b`, split on `a / d` so the
+// intermediate product stays within i128 for 18-decimal amounts.
+fn mul_div(a: i128, b: i128, d: i128) -> Result<i128, EscrowError> {
+    let whole = (a / d).checked_mul(b).ok_or(EscrowError::MathOverflow)?;
+    let part = (a % d).checked_mul(b).ok_or(EscrowError::MathOverflow)? / d;
+    whole.checked_add(part).ok_or(EscrowError::MathOverflow)
+}
+
+// `base` raised to `exp`, both in RATE_SCALE fixed point.
+fn pow_scaled(mut base: i128, mut exp: u64) -> Result<i128, EscrowError> {
+    let mut result = RATE_SCALE;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_div(result, base, RATE_SCALE)?;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = mul_div(base, base, RATE_SCALE)?;
+        }
+    }
+    Ok(result)
+}
+
+fn accrue_interest(account: &mut LoanAccount, now: u64) -> Result<(), EscrowError> {
+    let rate = account.rate_bps as i128;
+    match account.mode {
+        InterestMode::Simple => {
+            // The truncated remainder carries over so frequent touches do not
+            // round the borrower's interest down. The principal is split on
+            // the denominator so only the sub-unit part carries a remainder.
+            let denominator = BPS_DENOMINATOR * DAYS_PER_YEAR * SECONDS_PER_DAY as i128;
+            let elapsed = now.saturating_sub(account.accrued_at) as i128;
+            let factor = rate.checked_mul(elapsed).ok_or(EscrowError::MathOverflow)?;
+            let whole = (account.principal / denominator)
+                .checked_mul(factor)
+                .ok_or(EscrowError::MathOverflow)?;
+            let part = (account.principal % denominator)
+                .checked_mul(factor)
+                .and_then(|p| p.checked_add(account.interest_remainder))
+                .ok_or(EscrowError::MathOverflow)?;
+            account.interest = account.interest
+                .checked_add(whole)
+                .and_then(|i| i.checked_add(part / denominator))
+                .ok_or(EscrowError::MathOverflow)?;
+            account.interest_remainder = part % denominator;
+            account.accrued_at = now;
+        }
+        InterestMode::Compound => {
+            // Only whole days are capitalised; the remainder carries over so
+            // the result does not depend on how often the loan is touched.
+            let days = now.saturating_sub(account.accrued_at) / SECONDS_PER_DAY;
+            if days == 0 {
+                return Ok(());
+            }
+            let daily = RATE_SCALE + rate * RATE_SCALE / (BPS_DENOMINATOR * DAYS_PER_YEAR);
+            let debt = account.principal.checked_add(account.interest).ok_or(EscrowError::MathOverflow)?;
+            let grown = mul_div(debt, pow_scaled(daily, days)?, RATE_SCALE)?;
+            account.interest = account.interest
+                .checked_add(grown - debt)
+                .ok_or(EscrowError::MathOverflow)?;
+            account.accrued_at += days * SECONDS_PER_DAY;
+        }
+    }
+    Ok(())
+}
+
+// Principal plus interest accrued to now, or the recorded loan amount for
+// escrows whose repayment is not serviced on-chain.
+fn outstanding_debt(env: &Env, escrow: &EscrowData) -> Result<i128, EscrowError> {
+    let key = EscrowKey::LoanAccount(escrow.loan_id);
+    match env.storage().persistent().get::<_, LoanAccount>(&key) {
+        Some(mut account) => {
+            accrue_interest(&mut account, env.ledger().timestamp())?;
+            account.principal.checked_add(account.interest).ok_or(EscrowError::MathOverflow)
+        }
+        None => Ok(escrow.loan_amount),
+    }
+}
+
+fn collateral_haircut(env: &Env, token: &Address) -> u32 {
+    env.storage().persistent()
+        .get(&EscrowKey::CollateralHaircut(token.clone()))
+        .unwrap_or(0)
+}
+
+// Every collateral leg of an escrow, the primary token included.
+fn collateral_basket(escrow: &EscrowData) -> Map<Address, i128> {
+    let mut basket = escrow.basket.clone();
+    if escrow.collateral_amount > 0 {
+        basket.set(escrow.collateral_token.clone(), escrow.collateral_amount);
+    }
+    basket
+}
+
+fn collateral_balance(escrow: &EscrowData, token: &Address) -> i128 {
+    if *token == escrow.collateral_token {
+        escrow.collateral_amount
+    } else {
+        escrow.basket.get(token.clone()).unwrap_or(0)
+    }
+}
+
+fn set_collateral_balance(escrow: &mut EscrowData, token: &Address, amount: i128) {
+    if *token == escrow.collateral_token {
+        escrow.collateral_amount = amount;
+    } else if amount == 0 {
+        escrow.basket.remove(token.clone());
+    } else {
+        escrow.basket.set(token.clone(), amount);
+    }
+}
+
+fn release_basket(env: &Env, escrow: &EscrowData, to: &Address) {
+    for (token, amount) in collateral_basket(escrow).iter() {
+        token::Client::new(env, &token).transfer(&env.current_contract_address(), to, &amount);
+        adjust_total_locked(env, &token, -amount);
+    }
+}
+
+// Haircut-adjusted collateral value summed across the basket: oracle-priced
+// when an oracle is configured, raw token amounts otherwise. With
+// `apply_threshold` each leg is further weighted by its liquidation LTV.
+fn collateral_value(env: &Env, escrow: &EscrowData, apply_threshold: bool) -> Result<i128, EscrowError> {
+    let config: Option<OracleConfig> = env.storage().persistent().get(&EscrowKey::Oracle);
+
+    let mut total = 0;
+    for (token, amount) in collateral_basket(escrow).iter() {
+        let price = match &config {
+            Some(c) => oracle_price(env, c, &token)?,
+            None => 1,
+        };
+
+        let haircut = collateral_haircut(env, &token) as i128;
+        let mut value = amount * price * (BPS_DENOMINATOR - haircut) / BPS_DENOMINATOR;
+
+        if apply_threshold {
+            let threshold: u32 = env.storage().persistent()
+                .get(&EscrowKey::LiquidationThreshold(token.clone()))
+                .ok_or(EscrowError::ThresholdNotSet)?;
+            value = value * threshold as i128 / BPS_DENOMINATOR;
+        }
+
+        total += value;
+    }
+    Ok(total)
+}
+
+fn debt_value(env: &Env, escrow: &EscrowData) -> Result<i128, EscrowError> {
+    let config: OracleConfig = match env.storage().persistent().get(&EscrowKey::Oracle) {
+        Some(c) => c,
+        None => return outstanding_debt(env, escrow),
+    };
+
+    outstanding_debt(env, escrow)?
+        .checked_mul(oracle_price(env, &config, &read_loan_token(env)?)?)
+        .ok_or(EscrowError::MathOverflow)
+}
+
+fn meets_collateral_ratio(env: &Env, escrow: &EscrowData) -> Result<bool, EscrowError> {
+    Ok(collateral_value(env, escrow, false)? * BPS_DENOMINATOR
+        >= debt_value(env, escrow)? * min_collateral_ratio(env) as i128)
 }
 
+// Health factor in basis points: threshold-weighted collateral value over
+// debt value. Below 10_000 the position is liquidatable. `None` when no
+// oracle is configured.
+fn health_factor(env: &Env, escrow: &EscrowData) -> Result<Option<i128>, EscrowError> {
+    if !env.storage().persistent().has(&EscrowKey::Oracle) {
+        return Ok(None);
+    }
+
+    let debt = debt_value(env, escrow)?;
+    if debt == 0 {
+        return Ok(Some(i128::MAX));
+    }
+
+    Ok(Some(collateral_value(env, escrow, true)? * BPS_DENOMINATOR / debt))
+}
 
 
 
@@ -52,12 +896,16 @@ pub struct EscrowContract;
 #[contractimpl]
 impl EscrowContract {
     
-    pub fn initialize(env: Env, admin: Address) {
+    pub fn initialize(env: Env, admin: Address) -> Result<(), EscrowError> {
         if env.storage().persistent().has(&EscrowKey::Admin) {
-            panic!("Contract already initialized");
+            return Err(EscrowError::AlreadyInitialized);
         }
         env.storage().persistent().set(&EscrowKey::Admin, &admin);
         env.storage().persistent().set(&EscrowKey::TotalLocked, &0i128);
+        env.storage().instance().extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+        extend_config_ttl(&env);
+
+        Ok(())
     }
 
     
@@ -79,18 +927,18 @@ impl EscrowContract {
         collateral_amount: i128,
         loan_amount: i128,
         duration_seconds: u64,
-    ) -> bool {
+    ) -> Result<(), EscrowError> {
         
         borrower.require_auth();
 
         
         if collateral_amount <= 0 || loan_amount <= 0 {
-            return false;
+            return Err(EscrowError::InvalidAmount);
         }
 
         
         if env.storage().persistent().has(&EscrowKey::Escrow(loan_id)) {
-            return false;
+            return Err(EscrowError::EscrowExists);
         }
 
         
@@ -109,16 +957,17 @@ impl EscrowContract {
             due_date: env.ledger().timestamp() + duration_seconds,
             is_locked: true,
             is_liquidated: false,
+            basket: Map::new(&env),
         };
 
         
         env.storage().persistent().set(&EscrowKey::Escrow(loan_id), &escrow);
+        extend_escrow_ttl(&env, loan_id);
+        index_loan(&env, EscrowKey::BorrowerLoans(borrower.clone()), loan_id);
+        index_loan(&env, EscrowKey::LenderLoans(lender.clone()), loan_id);
 
         
-        let total: i128 = env.storage().persistent()
-            .get(&EscrowKey::TotalLocked)
-            .unwrap_or(0);
-        env.storage().persistent().set(&EscrowKey::TotalLocked, &(total + collateral_amount));
+        adjust_total_locked(&env, &escrow.collateral_token, collateral_amount);
 
         
         env.events().publish(
@@ -126,31 +975,20 @@ impl EscrowContract {
             (loan_id, borrower, collateral_amount),
         );
 
-        true
+        Ok(())
     }
 
     
-    pub fn release_collateral(env: Env, loan_id: u64) -> bool {
+    pub fn release_collateral(env: Env, loan_id: u64) -> Result<(), EscrowError> {
         
-        let escrow: EscrowData = match env.storage().persistent().get(&EscrowKey::Escrow(loan_id)) {
-            Some(e) => e,
-            None => return false,
-        };
-
-        if !escrow.is_locked || escrow.is_liquidated {
-            return false;
-        }
+        let escrow = read_escrow(&env, loan_id)?;
+        ensure_active(&escrow)?;
 
         
         escrow.lender.require_auth();
 
         
-        let client = token::Client::new(&env, &escrow.collateral_token);
-        client.transfer(
-            &env.current_contract_address(),
-            &escrow.borrower,
-            &escrow.collateral_amount,
-        );
+        release_basket(&env, &escrow, &escrow.borrower);
 
         
         let mut updated_escrow = escrow.clone();
@@ -159,72 +997,1094 @@ impl EscrowContract {
         env.storage().persistent().set(&EscrowKey::Escrow(loan_id), &updated_escrow);
 
         
-        let total: i128 = env.storage().persistent()
-            .get(&EscrowKey::TotalLocked)
-            .unwrap_or(0);
-        env.storage().persistent().set(&EscrowKey::TotalLocked, &(total - escrow.collateral_amount));
-
-        
         env.events().publish(
             (Symbol::new(&env, "CollateralReleased"),),
             (loan_id, escrow.borrower, escrow.collateral_amount),
         );
 
-        true
+        Ok(())
     }
 
     
-    
-    pub fn liquidate(env: Env, loan_id: u64) -> bool {
-        
-        let admin: Address = env.storage().persistent()
-            .get(&EscrowKey::Admin)
-            .expect("Contract not initialized");
-
-        
-        admin.require_auth();
+    // Both parties agree the interest terms before any repayment is taken.
+    // Interest accrues from the moment the terms are set.
+    pub fn configure_loan(
+        env: Env,
+        loan_id: u64,
+        rate_bps: u32,
+        mode: InterestMode,
+    ) -> Result<(), EscrowError> {
+        let escrow = read_escrow(&env, loan_id)?;
+        ensure_active(&escrow)?;
 
-        
-        let escrow: EscrowData = match env.storage().persistent().get(&EscrowKey::Escrow(loan_id)) {
-            Some(e) => e,
-            None => return false,
-        };
+        escrow.lender.require_auth();
+        escrow.borrower.require_auth();
 
-        if !escrow.is_locked || escrow.is_liquidated {
-            return false;
+        if env.storage().persistent().has(&EscrowKey::LoanAccount(loan_id)) {
+            return Err(EscrowError::LoanAlreadyConfigured);
+        }
+        if rate_bps > MAX_INTEREST_RATE_BPS {
+            return Err(EscrowError::InvalidConfig);
         }
 
-        
-        
+        let account = LoanAccount {
+            loan_id,
+            rate_bps,
+            mode,
+            principal: escrow.loan_amount,
+            interest: 0,
+            interest_remainder: 0,
+            accrued_at: env.ledger().timestamp(),
+            total_repaid: 0,
+        };
+        env.storage().persistent().set(&EscrowKey::LoanAccount(loan_id), &account);
 
-        
-        let client = token::Client::new(&env, &escrow.collateral_token);
-        client.transfer(
-            &env.current_contract_address(),
-            &escrow.lender,
-            &escrow.collateral_amount,
+        env.events().publish(
+            (Symbol::new(&env, "LoanConfigured"),),
+            (loan_id, rate_bps, mode),
         );
 
-        
-        let mut updated_escrow = escrow.clone();
-        updated_escrow.is_locked = false;
-        updated_escrow.is_liquidated = true;
+        Ok(())
+    }
 
-        env.storage().persistent().set(&EscrowKey::Escrow(loan_id), &updated_escrow);
+    
+    // Pays the lender in the loan token and returns the debt still owed.
+    // Overpayments are capped at the outstanding balance. Once the debt
+    // reaches zero the collateral goes straight back to the borrower.
+    pub fn repay(
+        env: Env,
+        loan_id: u64,
+        payer: Address,
+        amount: i128,
+    ) -> Result<i128, EscrowError> {
+        payer.require_auth();
 
-        
-        let total: i128 = env.storage().persistent()
-            .get(&EscrowKey::TotalLocked)
-            .unwrap_or(0);
-        env.storage().persistent().set(&EscrowKey::TotalLocked, &(total - escrow.collateral_amount));
+        if amount <= 0 {
+            return Err(EscrowError::InvalidAmount);
+        }
+
+        let mut escrow = read_escrow(&env, loan_id)?;
+        ensure_active(&escrow)?;
+
+        let mut account: LoanAccount = env.storage().persistent()
+            .get(&EscrowKey::LoanAccount(loan_id))
+            .ok_or(EscrowError::LoanNotConfigured)?;
+        let loan_token = read_loan_token(&env)?;
+
+        accrue_interest(&mut account, env.ledger().timestamp())?;
+
+        let payment = amount.min(account.principal + account.interest);
+        let to_interest = payment.min(account.interest);
+        account.interest -= to_interest;
+        account.principal -= payment - to_interest;
+        account.total_repaid += payment;
+
+        token::Client::new(&env, &loan_token).transfer(&payer, &escrow.lender, &payment);
+
+        let remaining = account.principal + account.interest;
+        env.storage().persistent().set(&EscrowKey::LoanAccount(loan_id), &account);
+
+        env.events().publish(
+            (Symbol::new(&env, "LoanRepaid"), payer),
+            (loan_id, payment, remaining),
+        );
+
+        if remaining == 0 {
+            release_basket(&env, &escrow, &escrow.borrower);
+            escrow.is_locked = false;
+            env.storage().persistent().set(&EscrowKey::Escrow(loan_id), &escrow);
+
+            env.events().publish(
+                (Symbol::new(&env, "CollateralReleased"),),
+                (loan_id, escrow.borrower, escrow.collateral_amount),
+            );
+        }
+
+        Ok(remaining)
+    }
+
+    
+    pub fn get_loan_account(env: Env, loan_id: u64) -> Option<LoanAccount> {
+        env.storage().persistent().get(&EscrowKey::LoanAccount(loan_id))
+    }
+
+    
+    pub fn get_outstanding_debt(env: Env, loan_id: u64) -> Result<i128, EscrowError> {
+        let escrow = read_escrow(&env, loan_id)?;
+        outstanding_debt(&env, &escrow)
+    }
+
+    
+    pub fn add_collateral(env: Env, loan_id: u64, amount: i128) -> Result<(), EscrowError> {
+        let escrow = read_escrow(&env, loan_id)?;
+        Self::add_collateral_token(env, loan_id, escrow.collateral_token, amount)
+    }
+
+    
+    // Tokens other than the escrow's primary collateral must have a haircut
+    // configured by the admin before they are accepted into the basket.
+    pub fn add_collateral_token(
+        env: Env,
+        loan_id: u64,
+        token: Address,
+        amount: i128,
+    ) -> Result<(), EscrowError> {
+        if amount <= 0 {
+            return Err(EscrowError::InvalidAmount);
+        }
+
+        let mut escrow = read_escrow(&env, loan_id)?;
+        ensure_active(&escrow)?;
+
+        if token != escrow.collateral_token
+            && !env.storage().persistent().has(&EscrowKey::CollateralHaircut(token.clone()))
+        {
+            return Err(EscrowError::TokenNotAccepted);
+        }
+
+        escrow.borrower.require_auth();
+
+        let client = token::Client::new(&env, &token);
+        client.transfer(&escrow.borrower, &env.current_contract_address(), &amount);
+
+        let balance = collateral_balance(&escrow, &token) + amount;
+        set_collateral_balance(&mut escrow, &token, balance);
+        env.storage().persistent().set(&EscrowKey::Escrow(loan_id), &escrow);
+
+        adjust_total_locked(&env, &token, amount);
+
+        env.events().publish(
+            (Symbol::new(&env, "CollateralAdded"),),
+            (loan_id, escrow.borrower, token, amount, balance),
+        );
+
+        Ok(())
+    }
+
+    
+    pub fn withdraw_collateral(env: Env, loan_id: u64, amount: i128) -> Result<(), EscrowError> {
+        let escrow = read_escrow(&env, loan_id)?;
+        Self::withdraw_collateral_token(env, loan_id, escrow.collateral_token, amount)
+    }
+
+    
+    // The remaining basket must still cover the loan at the configured
+    // minimum collateral ratio.
+    pub fn withdraw_collateral_token(
+        env: Env,
+        loan_id: u64,
+        token: Address,
+        amount: i128,
+    ) -> Result<(), EscrowError> {
+        if amount <= 0 {
+            return Err(EscrowError::InvalidAmount);
+        }
+
+        let mut escrow = read_escrow(&env, loan_id)?;
+        ensure_active(&escrow)?;
+
+        escrow.borrower.require_auth();
+
+        let balance = collateral_balance(&escrow, &token);
+        if amount > balance {
+            return Err(EscrowError::InsufficientCollateral);
+        }
+
+        let remaining = balance - amount;
+        set_collateral_balance(&mut escrow, &token, remaining);
+        if !meets_collateral_ratio(&env, &escrow)? {
+            return Err(EscrowError::CollateralRatioTooLow);
+        }
+
+        let client = token::Client::new(&env, &token);
+        client.transfer(&env.current_contract_address(), &escrow.borrower, &amount);
+
+        env.storage().persistent().set(&EscrowKey::Escrow(loan_id), &escrow);
+
+        adjust_total_locked(&env, &token, -amount);
+
+        env.events().publish(
+            (Symbol::new(&env, "CollateralWithdrawn"),),
+            (loan_id, escrow.borrower, token, amount, remaining),
+        );
+
+        Ok(())
+    }
+
+    
+    
+    pub fn liquidate(env: Env, loan_id: u64) -> Result<(), EscrowError> {
+        
+        let admin = read_admin(&env)?;
+
+        
+        authorize_admin_action(&env, AdminAction::Liquidate(loan_id))?;
+
+        
+        let escrow = read_escrow(&env, loan_id)?;
+        ensure_active(&escrow)?;
+
+        
+        if let Some(health) = health_factor(&env, &escrow)? {
+            if health >= BPS_DENOMINATOR {
+                return Err(EscrowError::PositionHealthy);
+            }
+        }
+
+        
+        if auction_configured(&env, &escrow) {
+            return start_auction(&env, &escrow, &admin);
+        }
+
+        
+        release_basket(&env, &escrow, &escrow.lender);
+
+        
+        let mut updated_escrow = escrow.clone();
+        updated_escrow.is_locked = false;
+        updated_escrow.is_liquidated = true;
+
+        env.storage().persistent().set(&EscrowKey::Escrow(loan_id), &updated_escrow);
+
+        
+        env.events().publish(
+            (Symbol::new(&env, "Liquidated"),),
+            (loan_id, escrow.borrower, escrow.collateral_amount),
+        );
+
+        Ok(())
+    }
+
+    
+    // Open to any caller once the escrow is past due by more than the grace
+    // period, or as soon as its health factor drops below 1.
+    pub fn liquidate_by(env: Env, loan_id: u64, liquidator: Address) -> Result<(), EscrowError> {
+        liquidator.require_auth();
+
+        let escrow = read_escrow(&env, loan_id)?;
+        ensure_active(&escrow)?;
+
+        let config = liquidation_config(&env);
+        let overdue = env.ledger().timestamp() > escrow.due_date + config.grace_period;
+        let eligible = overdue
+            || matches!(health_factor(&env, &escrow)?, Some(health) if health < BPS_DENOMINATOR);
+        if !eligible {
+            return Err(EscrowError::NotLiquidatable);
+        }
+
+        if auction_configured(&env, &escrow) {
+            return start_auction(&env, &escrow, &liquidator);
+        }
+
+        let mut lender_shares = Map::new(&env);
+        let mut liquidator_bonuses = Map::new(&env);
+        for (token, amount) in collateral_basket(&escrow).iter() {
+            let liquidator_bonus = amount * config.liquidator_bonus_bps as i128 / BPS_DENOMINATOR;
+            let lender_share = amount - liquidator_bonus;
+
+            let client = token::Client::new(&env, &token);
+            client.transfer(&env.current_contract_address(), &escrow.lender, &lender_share);
+            if liquidator_bonus > 0 {
+                client.transfer(&env.current_contract_address(), &liquidator, &liquidator_bonus);
+            }
+
+            adjust_total_locked(&env, &token, -amount);
+
+            lender_shares.set(token.clone(), lender_share);
+            liquidator_bonuses.set(token, liquidator_bonus);
+        }
+
+        let mut updated_escrow = escrow.clone();
+        updated_escrow.is_locked = false;
+        updated_escrow.is_liquidated = true;
+
+        env.storage().persistent().set(&EscrowKey::Escrow(loan_id), &updated_escrow);
+
+        env.events().publish(
+            (Symbol::new(&env, "Liquidated"), liquidator),
+            (loan_id, escrow.borrower, lender_shares, liquidator_bonuses),
+        );
+
+        Ok(())
+    }
+
+    
+    // Buys the whole `token` lot at the current auction price, paid in the
+    // loan token. Proceeds go to the lender until the debt plus penalty is
+    // covered and any surplus to the borrower. Once the debt is covered the
+    // unsold lots go back to the borrower.
+    pub fn bid(
+        env: Env,
+        loan_id: u64,
+        token: Address,
+        bidder: Address,
+    ) -> Result<i128, EscrowError> {
+        bidder.require_auth();
+
+        let mut auction: Auction = env.storage().persistent()
+            .get(&EscrowKey::Auction(loan_id))
+            .ok_or(EscrowError::AuctionNotFound)?;
+
+        if !auction.is_active {
+            return Err(EscrowError::AuctionClosed);
+        }
+
+        let lot = auction.lots.get(token.clone()).ok_or(EscrowError::LotNotFound)?;
+        let escrow = read_escrow(&env, loan_id)?;
+        let loan_token = read_loan_token(&env)?;
+
+        let price = lot_price(&lot, env.ledger().timestamp() - auction.started_at);
+        let to_lender = price.min(auction.debt_remaining);
+        let to_borrower = price - to_lender;
+
+        let payment = token::Client::new(&env, &loan_token);
+        if to_lender > 0 {
+            payment.transfer(&bidder, &escrow.lender, &to_lender);
+        }
+        if to_borrower > 0 {
+            payment.transfer(&bidder, &escrow.borrower, &to_borrower);
+        }
+
+        token::Client::new(&env, &token)
+            .transfer(&env.current_contract_address(), &bidder, &lot.amount);
+        adjust_total_locked(&env, &token, -lot.amount);
+
+        auction.lots.remove(token.clone());
+        auction.debt_remaining -= to_lender;
+        auction.proceeds += price;
+
+        env.events().publish(
+            (Symbol::new(&env, "AuctionBid"), bidder),
+            (loan_id, token, lot.amount, price),
+        );
+
+        if auction.debt_remaining == 0 {
+            for (remaining_token, remaining_lot) in auction.lots.iter() {
+                token::Client::new(&env, &remaining_token)
+                    .transfer(&env.current_contract_address(), &escrow.borrower, &remaining_lot.amount);
+                adjust_total_locked(&env, &remaining_token, -remaining_lot.amount);
+            }
+            auction.lots = Map::new(&env);
+        }
+
+        if auction.lots.is_empty() {
+            settle_auction(&env, &mut auction);
+        }
+
+        env.storage().persistent().set(&EscrowKey::Auction(loan_id), &auction);
+        extend_agreement_ttl(&env, &EscrowKey::Auction(loan_id));
+
+        Ok(price)
+    }
+
+    
+    // Ends an auction nobody has bid on once every lot has sat at its floor
+    // price for AUCTION_FLOOR_PERIOD. The unsold lots go to the lender, as
+    // they would in a liquidation without an auction. Callable by anyone.
+    pub fn close_auction(env: Env, loan_id: u64) -> Result<(), EscrowError> {
+        let mut auction: Auction = env.storage().persistent()
+            .get(&EscrowKey::Auction(loan_id))
+            .ok_or(EscrowError::AuctionNotFound)?;
+
+        if !auction.is_active {
+            return Err(EscrowError::AuctionClosed);
+        }
+
+        let longest = auction.lots.values().iter().map(|lot| lot.duration).max().unwrap_or(0);
+        let expires_at = auction.started_at
+            .saturating_add(longest)
+            .saturating_add(AUCTION_FLOOR_PERIOD);
+        if env.ledger().timestamp() <= expires_at {
+            return Err(EscrowError::TimeoutNotReached);
+        }
+
+        let escrow = read_escrow(&env, loan_id)?;
+        for (token, lot) in auction.lots.iter() {
+            token::Client::new(&env, &token)
+                .transfer(&env.current_contract_address(), &escrow.lender, &lot.amount);
+            adjust_total_locked(&env, &token, -lot.amount);
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "AuctionExpired"),),
+            (loan_id, escrow.lender, auction.lots.clone()),
+        );
+
+        auction.lots = Map::new(&env);
+        settle_auction(&env, &mut auction);
+        env.storage().persistent().set(&EscrowKey::Auction(loan_id), &auction);
+        extend_agreement_ttl(&env, &EscrowKey::Auction(loan_id));
+
+        Ok(())
+    }
+
+    
+    pub fn get_auction(env: Env, loan_id: u64) -> Option<Auction> {
+        env.storage().persistent().get(&EscrowKey::Auction(loan_id))
+    }
+
+    
+    pub fn get_auction_price(env: Env, loan_id: u64, token: Address) -> Result<i128, EscrowError> {
+        let auction: Auction = env.storage().persistent()
+            .get(&EscrowKey::Auction(loan_id))
+            .ok_or(EscrowError::AuctionNotFound)?;
+
+        let lot = auction.lots.get(token).ok_or(EscrowError::LotNotFound)?;
+        Ok(lot_price(&lot, env.ledger().timestamp() - auction.started_at))
+    }
+
+    
+    // Buyer/seller escrow for marketplace payments. The buyer funds the deal
+    // up front; `release_timeout` is how long the buyer has to confirm or
+    // dispute after the seller marks the deal delivered, and also how long
+    // the seller has to deliver before the buyer can take a refund.
+    pub fn create_deal(
+        env: Env,
+        buyer: Address,
+        seller: Address,
+        arbiter: Option<Address>,
+        token: Address,
+        amount: i128,
+        release_timeout: u64,
+    ) -> Result<u64, EscrowError> {
+        buyer.require_auth();
+
+        if amount <= 0 {
+            return Err(EscrowError::InvalidAmount);
+        }
+
+        let client = token::Client::new(&env, &token);
+        client.transfer(&buyer, &env.current_contract_address(), &amount);
+
+        let deal_id: u64 = env.storage().persistent()
+            .get(&EscrowKey::DealCounter)
+            .unwrap_or(0) + 1;
+
+        let deal = Deal {
+            deal_id,
+            buyer: buyer.clone(),
+            seller: seller.clone(),
+            arbiter,
+            token,
+            amount,
+            created_at: env.ledger().timestamp(),
+            delivered_at: 0,
+            release_timeout,
+            status: DealStatus::Funded,
+        };
+
+        env.storage().persistent().set(&EscrowKey::Deal(deal_id), &deal);
+        env.storage().persistent().set(&EscrowKey::DealCounter, &deal_id);
+        extend_agreement_ttl(&env, &EscrowKey::Deal(deal_id));
+        extend_persistent_ttl(&env, &EscrowKey::DealCounter);
+
+        adjust_total_locked(&env, &deal.token, amount);
+
+        env.events().publish(
+            (Symbol::new(&env, "DealCreated"),),
+            (deal_id, buyer, seller, amount),
+        );
+
+        Ok(deal_id)
+    }
+
+    
+    pub fn mark_delivered(env: Env, deal_id: u64) -> Result<(), EscrowError> {
+        let mut deal: Deal = env.storage().persistent()
+            .get(&EscrowKey::Deal(deal_id))
+            .ok_or(EscrowError::DealNotFound)?;
+
+        if deal.status != DealStatus::Funded {
+            return Err(EscrowError::InvalidDealState);
+        }
+
+        deal.seller.require_auth();
+
+        deal.status = DealStatus::Delivered;
+        deal.delivered_at = env.ledger().timestamp();
+        env.storage().persistent().set(&EscrowKey::Deal(deal_id), &deal);
+        extend_agreement_ttl(&env, &EscrowKey::Deal(deal_id));
+
+        env.events().publish(
+            (Symbol::new(&env, "DealDelivered"),),
+            (deal_id, deal.seller),
+        );
+
+        Ok(())
+    }
+
+    
+    pub fn confirm_delivery(env: Env, deal_id: u64) -> Result<(), EscrowError> {
+        let mut deal: Deal = env.storage().persistent()
+            .get(&EscrowKey::Deal(deal_id))
+            .ok_or(EscrowError::DealNotFound)?;
+
+        if deal.status != DealStatus::Funded && deal.status != DealStatus::Delivered {
+            return Err(EscrowError::InvalidDealState);
+        }
+
+        deal.buyer.require_auth();
+
+        let client = token::Client::new(&env, &deal.token);
+        client.transfer(&env.current_contract_address(), &deal.seller, &deal.amount);
+
+        deal.status = DealStatus::Completed;
+        env.storage().persistent().set(&EscrowKey::Deal(deal_id), &deal);
+        extend_agreement_ttl(&env, &EscrowKey::Deal(deal_id));
+
+        adjust_total_locked(&env, &deal.token, -deal.amount);
+
+        env.events().publish(
+            (Symbol::new(&env, "DealCompleted"),),
+            (deal_id, deal.seller, deal.amount),
+        );
+
+        Ok(())
+    }
+
+    
+    pub fn open_dispute(env: Env, deal_id: u64) -> Result<(), EscrowError> {
+        let mut deal: Deal = env.storage().persistent()
+            .get(&EscrowKey::Deal(deal_id))
+            .ok_or(EscrowError::DealNotFound)?;
+
+        if deal.arbiter.is_none() {
+            return Err(EscrowError::NoArbiter);
+        }
+
+        if deal.status != DealStatus::Funded && deal.status != DealStatus::Delivered {
+            return Err(EscrowError::InvalidDealState);
+        }
+
+        deal.buyer.require_auth();
+
+        deal.status = DealStatus::Disputed;
+        env.storage().persistent().set(&EscrowKey::Deal(deal_id), &deal);
+        extend_agreement_ttl(&env, &EscrowKey::Deal(deal_id));
+
+        env.events().publish(
+            (Symbol::new(&env, "DealDisputed"),),
+            (deal_id, deal.buyer),
+        );
+
+        Ok(())
+    }
+
+    
+    // The arbiter may split the escrowed amount any way between the two
+    // parties; whatever is not awarded to the seller goes back to the buyer.
+    pub fn resolve_dispute(env: Env, deal_id: u64, seller_amount: i128) -> Result<(), EscrowError> {
+        let mut deal: Deal = env.storage().persistent()
+            .get(&EscrowKey::Deal(deal_id))
+            .ok_or(EscrowError::DealNotFound)?;
+
+        if deal.status != DealStatus::Disputed {
+            return Err(EscrowError::InvalidDealState);
+        }
+
+        let arbiter = deal.arbiter.clone().ok_or(EscrowError::NoArbiter)?;
+        arbiter.require_auth();
+
+        if seller_amount < 0 || seller_amount > deal.amount {
+            return Err(EscrowError::InvalidSplit);
+        }
+        let buyer_amount = deal.amount - seller_amount;
+
+        let client = token::Client::new(&env, &deal.token);
+        if seller_amount > 0 {
+            client.transfer(&env.current_contract_address(), &deal.seller, &seller_amount);
+        }
+        if buyer_amount > 0 {
+            client.transfer(&env.current_contract_address(), &deal.buyer, &buyer_amount);
+        }
+
+        deal.status = DealStatus::Resolved;
+        env.storage().persistent().set(&EscrowKey::Deal(deal_id), &deal);
+        extend_agreement_ttl(&env, &EscrowKey::Deal(deal_id));
+
+        adjust_total_locked(&env, &deal.token, -deal.amount);
+
+        env.events().publish(
+            (Symbol::new(&env, "DealResolved"), arbiter),
+            (deal_id, seller_amount, buyer_amount),
+        );
+
+        Ok(())
+    }
+
+    
+    // Pays the seller once the buyer has stayed silent past the release
+    // timeout following delivery. Callable by anyone.
+    pub fn claim_auto_release(env: Env, deal_id: u64) -> Result<(), EscrowError> {
+        let mut deal: Deal = env.storage().persistent()
+            .get(&EscrowKey::Deal(deal_id))
+            .ok_or(EscrowError::DealNotFound)?;
+
+        if deal.status != DealStatus::Delivered {
+            return Err(EscrowError::InvalidDealState);
+        }
+
+        if env.ledger().timestamp() <= deal.delivered_at + deal.release_timeout {
+            return Err(EscrowError::TimeoutNotReached);
+        }
+
+        let client = token::Client::new(&env, &deal.token);
+        client.transfer(&env.current_contract_address(), &deal.seller, &deal.amount);
+
+        deal.status = DealStatus::Completed;
+        env.storage().persistent().set(&EscrowKey::Deal(deal_id), &deal);
+        extend_agreement_ttl(&env, &EscrowKey::Deal(deal_id));
+
+        adjust_total_locked(&env, &deal.token, -deal.amount);
+
+        env.events().publish(
+            (Symbol::new(&env, "DealAutoReleased"),),
+            (deal_id, deal.seller, deal.amount),
+        );
+
+        Ok(())
+    }
+
+    
+    // Returns the funds to the buyer when the seller has not delivered within
+    // the release timeout of the deal being created. This is the only way out
+    // of a deal without an arbiter if the seller disappears.
+    pub fn refund_undelivered(env: Env, deal_id: u64) -> Result<(), EscrowError> {
+        let mut deal: Deal = env.storage().persistent()
+            .get(&EscrowKey::Deal(deal_id))
+            .ok_or(EscrowError::DealNotFound)?;
+
+        if deal.status != DealStatus::Funded {
+            return Err(EscrowError::InvalidDealState);
+        }
+
+        if env.ledger().timestamp() <= deal.created_at + deal.release_timeout {
+            return Err(EscrowError::TimeoutNotReached);
+        }
+
+        let client = token::Client::new(&env, &deal.token);
+        client.transfer(&env.current_contract_address(), &deal.buyer, &deal.amount);
+
+        deal.status = DealStatus::Refunded;
+        env.storage().persistent().set(&EscrowKey::Deal(deal_id), &deal);
+        extend_agreement_ttl(&env, &EscrowKey::Deal(deal_id));
+
+        adjust_total_locked(&env, &deal.token, -deal.amount);
+
+        env.events().publish(
+            (Symbol::new(&env, "DealRefunded"),),
+            (deal_id, deal.buyer, deal.amount),
+        );
+
+        Ok(())
+    }
+
+    
+    pub fn get_deal(env: Env, deal_id: u64) -> Option<Deal> {
+        env.storage().persistent().get(&EscrowKey::Deal(deal_id))
+    }
+
+    
+    // Staged payments: the funder escrows the sum of all milestone amounts up
+    // front. Deadlines must be in the future and in non-decreasing order.
+    pub fn create_milestone_escrow(
+        env: Env,
+        funder: Address,
+        payee: Address,
+        arbiter: Option<Address>,
+        token: Address,
+        amounts: Vec<i128>,
+        deadlines: Vec<u64>,
+    ) -> Result<u64, EscrowError> {
+        funder.require_auth();
+
+        if amounts.is_empty() || amounts.len() != deadlines.len() {
+            return Err(EscrowError::InvalidMilestones);
+        }
+
+        let mut milestones = Vec::new(&env);
+        let mut total: i128 = 0;
+        let mut previous_deadline = env.ledger().timestamp();
+        for (amount, deadline) in amounts.iter().zip(deadlines.iter()) {
+            if amount <= 0 || deadline <= env.ledger().timestamp() || deadline < previous_deadline {
+                return Err(EscrowError::InvalidMilestones);
+            }
+            previous_deadline = deadline;
+            total += amount;
+            milestones.push_back(Milestone {
+                amount,
+                deadline,
+                status: MilestoneStatus::Pending,
+                claimed_at: 0,
+            });
+        }
+
+        let client = token::Client::new(&env, &token);
+        client.transfer(&funder, &env.current_contract_address(), &total);
+
+        let escrow_id: u64 = env.storage().persistent()
+            .get(&EscrowKey::MilestoneCounter)
+            .unwrap_or(0) + 1;
+
+        let escrow = MilestoneEscrow {
+            escrow_id,
+            funder: funder.clone(),
+            payee: payee.clone(),
+            arbiter,
+            token,
+            milestones,
+            created_at: env.ledger().timestamp(),
+        };
+
+        env.storage().persistent().set(&EscrowKey::MilestoneEscrow(escrow_id), &escrow);
+        env.storage().persistent().set(&EscrowKey::MilestoneCounter, &escrow_id);
+        extend_agreement_ttl(&env, &EscrowKey::MilestoneEscrow(escrow_id));
+        extend_persistent_ttl(&env, &EscrowKey::MilestoneCounter);
+
+        adjust_total_locked(&env, &escrow.token, total);
+
+        env.events().publish(
+            (Symbol::new(&env, "MilestoneEscrowCreated"),),
+            (escrow_id, funder, payee, total),
+        );
+
+        Ok(escrow_id)
+    }
+
+    
+    // Milestones are claimed in order: every earlier milestone must have left
+    // the pending state first.
+    pub fn claim_milestone(env: Env, escrow_id: u64, index: u32) -> Result<(), EscrowError> {
+        let mut escrow: MilestoneEscrow = env.storage().persistent()
+            .get(&EscrowKey::MilestoneEscrow(escrow_id))
+            .ok_or(EscrowError::MilestoneEscrowNotFound)?;
+
+        let mut milestone = escrow.milestones.get(index).ok_or(EscrowError::MilestoneNotFound)?;
+
+        if milestone.status != MilestoneStatus::Pending {
+            return Err(EscrowError::InvalidMilestoneState);
+        }
+        if env.ledger().timestamp() > milestone.deadline {
+            return Err(EscrowError::DeadlinePassed);
+        }
+
+        for i in 0..index {
+            if escrow.milestones.get_unchecked(i).status == MilestoneStatus::Pending {
+                return Err(EscrowError::MilestoneOutOfOrder);
+            }
+        }
+
+        escrow.payee.require_auth();
+
+        milestone.status = MilestoneStatus::Claimed;
+        milestone.claimed_at = env.ledger().timestamp();
+        escrow.milestones.set(index, milestone.clone());
+        env.storage().persistent().set(&EscrowKey::MilestoneEscrow(escrow_id), &escrow);
+        extend_agreement_ttl(&env, &EscrowKey::MilestoneEscrow(escrow_id));
+
+        publish_milestone_event(&env, "MilestoneClaimed", escrow_id, index, &milestone);
+
+        Ok(())
+    }
+
+    
+    pub fn approve_milestone(env: Env, escrow_id: u64, index: u32) -> Result<(), EscrowError> {
+        let mut escrow: MilestoneEscrow = env.storage().persistent()
+            .get(&EscrowKey::MilestoneEscrow(escrow_id))
+            .ok_or(EscrowError::MilestoneEscrowNotFound)?;
+
+        let mut milestone = escrow.milestones.get(index).ok_or(EscrowError::MilestoneNotFound)?;
+
+        if milestone.status != MilestoneStatus::Claimed {
+            return Err(EscrowError::InvalidMilestoneState);
+        }
+
+        escrow.funder.require_auth();
+
+        let client = token::Client::new(&env, &escrow.token);
+        client.transfer(&env.current_contract_address(), &escrow.payee, &milestone.amount);
+
+        milestone.status = MilestoneStatus::Released;
+        escrow.milestones.set(index, milestone.clone());
+        env.storage().persistent().set(&EscrowKey::MilestoneEscrow(escrow_id), &escrow);
+        extend_agreement_ttl(&env, &EscrowKey::MilestoneEscrow(escrow_id));
+
+        adjust_total_locked(&env, &escrow.token, -milestone.amount);
+
+        publish_milestone_event(&env, "MilestoneReleased", escrow_id, index, &milestone);
+
+        Ok(())
+    }
+
+    
+    // Pays a claimed milestone to the payee once the funder has neither
+    // approved nor disputed it within the review period. Callable by anyone.
+    pub fn claim_milestone_auto_release(env: Env, escrow_id: u64, index: u32) -> Result<(), EscrowError> {
+        let mut escrow: MilestoneEscrow = env.storage().persistent()
+            .get(&EscrowKey::MilestoneEscrow(escrow_id))
+            .ok_or(EscrowError::MilestoneEscrowNotFound)?;
+
+        let mut milestone = escrow.milestones.get(index).ok_or(EscrowError::MilestoneNotFound)?;
+
+        if milestone.status != MilestoneStatus::Claimed {
+            return Err(EscrowError::InvalidMilestoneState);
+        }
+
+        if env.ledger().timestamp() <= milestone.claimed_at.saturating_add(MILESTONE_REVIEW_PERIOD) {
+            return Err(EscrowError::TimeoutNotReached);
+        }
+
+        let client = token::Client::new(&env, &escrow.token);
+        client.transfer(&env.current_contract_address(), &escrow.payee, &milestone.amount);
+
+        milestone.status = MilestoneStatus::Released;
+        escrow.milestones.set(index, milestone.clone());
+        env.storage().persistent().set(&EscrowKey::MilestoneEscrow(escrow_id), &escrow);
+        extend_agreement_ttl(&env, &EscrowKey::MilestoneEscrow(escrow_id));
+
+        adjust_total_locked(&env, &escrow.token, -milestone.amount);
+
+        publish_milestone_event(&env, "MilestoneAutoReleased", escrow_id, index, &milestone);
+
+        Ok(())
+    }
+
+    
+    pub fn dispute_milestone(env: Env, escrow_id: u64, index: u32) -> Result<(), EscrowError> {
+        let mut escrow: MilestoneEscrow = env.storage().persistent()
+            .get(&EscrowKey::MilestoneEscrow(escrow_id))
+            .ok_or(EscrowError::MilestoneEscrowNotFound)?;
+
+        if escrow.arbiter.is_none() {
+            return Err(EscrowError::NoArbiter);
+        }
+
+        let mut milestone = escrow.milestones.get(index).ok_or(EscrowError::MilestoneNotFound)?;
+
+        if milestone.status != MilestoneStatus::Claimed {
+            return Err(EscrowError::InvalidMilestoneState);
+        }
+
+        escrow.funder.require_auth();
+
+        milestone.status = MilestoneStatus::Disputed;
+        escrow.milestones.set(index, milestone.clone());
+        env.storage().persistent().set(&EscrowKey::MilestoneEscrow(escrow_id), &escrow);
+        extend_agreement_ttl(&env, &EscrowKey::MilestoneEscrow(escrow_id));
+
+        publish_milestone_event(&env, "MilestoneDisputed", escrow_id, index, &milestone);
+
+        Ok(())
+    }
+
+    
+    // The arbiter either releases a disputed milestone to the payee or
+    // refunds it to the funder.
+    pub fn resolve_milestone(
+        env: Env,
+        escrow_id: u64,
+        index: u32,
+        release: bool,
+    ) -> Result<(), EscrowError> {
+        let mut escrow: MilestoneEscrow = env.storage().persistent()
+            .get(&EscrowKey::MilestoneEscrow(escrow_id))
+            .ok_or(EscrowError::MilestoneEscrowNotFound)?;
+
+        let arbiter = escrow.arbiter.clone().ok_or(EscrowError::NoArbiter)?;
+
+        let mut milestone = escrow.milestones.get(index).ok_or(EscrowError::MilestoneNotFound)?;
+
+        if milestone.status != MilestoneStatus::Disputed {
+            return Err(EscrowError::InvalidMilestoneState);
+        }
+
+        arbiter.require_auth();
+
+        let recipient = if release { &escrow.payee } else { &escrow.funder };
+        let client = token::Client::new(&env, &escrow.token);
+        client.transfer(&env.current_contract_address(), recipient, &milestone.amount);
+
+        milestone.status = if release {
+            MilestoneStatus::Released
+        } else {
+            MilestoneStatus::Refunded
+        };
+        escrow.milestones.set(index, milestone.clone());
+        env.storage().persistent().set(&EscrowKey::MilestoneEscrow(escrow_id), &escrow);
+        extend_agreement_ttl(&env, &EscrowKey::MilestoneEscrow(escrow_id));
+
+        adjust_total_locked(&env, &escrow.token, -milestone.amount);
+
+        let name = if release { "MilestoneReleased" } else { "MilestoneRefunded" };
+        publish_milestone_event(&env, name, escrow_id, index, &milestone);
+
+        Ok(())
+    }
+
+    
+    // Returns a milestone the payee never claimed once its deadline passes.
+    pub fn refund_milestone(env: Env, escrow_id: u64, index: u32) -> Result<(), EscrowError> {
+        let mut escrow: MilestoneEscrow = env.storage().persistent()
+            .get(&EscrowKey::MilestoneEscrow(escrow_id))
+            .ok_or(EscrowError::MilestoneEscrowNotFound)?;
+
+        let mut milestone = escrow.milestones.get(index).ok_or(EscrowError::MilestoneNotFound)?;
+
+        if milestone.status != MilestoneStatus::Pending {
+            return Err(EscrowError::InvalidMilestoneState);
+        }
+        if env.ledger().timestamp() <= milestone.deadline {
+            return Err(EscrowError::DeadlineNotReached);
+        }
+
+        escrow.funder.require_auth();
+
+        let client = token::Client::new(&env, &escrow.token);
+        client.transfer(&env.current_contract_address(), &escrow.funder, &milestone.amount);
+
+        milestone.status = MilestoneStatus::Refunded;
+        escrow.milestones.set(index, milestone.clone());
+        env.storage().persistent().set(&EscrowKey::MilestoneEscrow(escrow_id), &escrow);
+        extend_agreement_ttl(&env, &EscrowKey::MilestoneEscrow(escrow_id));
+
+        adjust_total_locked(&env, &escrow.token, -milestone.amount);
+
+        publish_milestone_event(&env, "MilestoneRefunded", escrow_id, index, &milestone);
+
+        Ok(())
+    }
+
+    
+    pub fn get_milestone_escrow(env: Env, escrow_id: u64) -> Option<MilestoneEscrow> {
+        env.storage().persistent().get(&EscrowKey::MilestoneEscrow(escrow_id))
+    }
+
+    
+    // Hash-time-locked transfer for cross-chain swaps. `hashlock` is the
+    // SHA-256 of a secret preimage; `timelock` is the ledger timestamp after
+    // which the sender can take the funds back.
+    pub fn create_htlc(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+        hashlock: BytesN<32>,
+        timelock: u64,
+    ) -> Result<u64, EscrowError> {
+        sender.require_auth();
+
+        if amount <= 0 {
+            return Err(EscrowError::InvalidAmount);
+        }
+        if timelock <= env.ledger().timestamp() {
+            return Err(EscrowError::InvalidTimelock);
+        }
+
+        let client = token::Client::new(&env, &token);
+        client.transfer(&sender, &env.current_contract_address(), &amount);
+
+        let htlc_id: u64 = env.storage().persistent()
+            .get(&EscrowKey::HtlcCounter)
+            .unwrap_or(0) + 1;
+
+        let htlc = Htlc {
+            htlc_id,
+            sender: sender.clone(),
+            recipient: recipient.clone(),
+            token,
+            amount,
+            hashlock: hashlock.clone(),
+            timelock,
+            preimage: None,
+            status: HtlcStatus::Locked,
+        };
+
+        env.storage().persistent().set(&EscrowKey::Htlc(htlc_id), &htlc);
+        env.storage().persistent().set(&EscrowKey::HtlcCounter, &htlc_id);
+        extend_agreement_ttl(&env, &EscrowKey::Htlc(htlc_id));
+        extend_persistent_ttl(&env, &EscrowKey::HtlcCounter);
+
+        adjust_total_locked(&env, &htlc.token, amount);
 
-        
         env.events().publish(
-            (Symbol::new(&env, "Liquidated"),),
-            (loan_id, escrow.borrower, escrow.collateral_amount),
+            (Symbol::new(&env, "HtlcCreated"), hashlock),
+            (htlc_id, sender, recipient, amount, timelock),
+        );
+
+        Ok(htlc_id)
+    }
+
+    
+    // Anyone holding the preimage may submit it; the funds always go to the
+    // recipient. The preimage is published so the other leg can be claimed.
+    pub fn claim_htlc(env: Env, htlc_id: u64, preimage: Bytes) -> Result<(), EscrowError> {
+        let mut htlc: Htlc = env.storage().persistent()
+            .get(&EscrowKey::Htlc(htlc_id))
+            .ok_or(EscrowError::HtlcNotFound)?;
+
+        if htlc.status != HtlcStatus::Locked {
+            return Err(EscrowError::InvalidHtlcState);
+        }
+        if env.ledger().timestamp() >= htlc.timelock {
+            return Err(EscrowError::HtlcExpired);
+        }
+
+        if env.crypto().sha256(&preimage).to_bytes() != htlc.hashlock {
+            return Err(EscrowError::InvalidPreimage);
+        }
+
+        let client = token::Client::new(&env, &htlc.token);
+        client.transfer(&env.current_contract_address(), &htlc.recipient, &htlc.amount);
+
+        htlc.status = HtlcStatus::Claimed;
+        htlc.preimage = Some(preimage.clone());
+        env.storage().persistent().set(&EscrowKey::Htlc(htlc_id), &htlc);
+        extend_agreement_ttl(&env, &EscrowKey::Htlc(htlc_id));
+
+        adjust_total_locked(&env, &htlc.token, -htlc.amount);
+
+        env.events().publish(
+            (Symbol::new(&env, "HtlcClaimed"), htlc.hashlock),
+            (htlc_id, htlc.recipient, preimage),
+        );
+
+        Ok(())
+    }
+
+    
+    pub fn refund_htlc(env: Env, htlc_id: u64) -> Result<(), EscrowError> {
+        let mut htlc: Htlc = env.storage().persistent()
+            .get(&EscrowKey::Htlc(htlc_id))
+            .ok_or(EscrowError::HtlcNotFound)?;
+
+        if htlc.status != HtlcStatus::Locked {
+            return Err(EscrowError::InvalidHtlcState);
+        }
+        if env.ledger().timestamp() < htlc.timelock {
+            return Err(EscrowError::HtlcNotExpired);
+        }
+
+        let client = token::Client::new(&env, &htlc.token);
+        client.transfer(&env.current_contract_address(), &htlc.sender, &htlc.amount);
+
+        htlc.status = HtlcStatus::Refunded;
+        env.storage().persistent().set(&EscrowKey::Htlc(htlc_id), &htlc);
+        extend_agreement_ttl(&env, &EscrowKey::Htlc(htlc_id));
+
+        adjust_total_locked(&env, &htlc.token, -htlc.amount);
+
+        env.events().publish(
+            (Symbol::new(&env, "HtlcRefunded"), htlc.hashlock),
+            (htlc_id, htlc.sender, htlc.amount),
         );
 
-        true
+        Ok(())
+    }
+
+    
+    pub fn get_htlc(env: Env, htlc_id: u64) -> Option<Htlc> {
+        env.storage().persistent().get(&EscrowKey::Htlc(htlc_id))
     }
 
     
@@ -233,6 +2093,28 @@ impl EscrowContract {
     }
 
     
+    pub fn list_escrows_by_borrower(
+        env: Env,
+        borrower: Address,
+        status: Option<EscrowStatus>,
+        cursor: u32,
+        limit: u32,
+    ) -> EscrowPage {
+        list_indexed(&env, EscrowKey::BorrowerLoans(borrower), status, cursor, limit)
+    }
+
+    
+    pub fn list_escrows_by_lender(
+        env: Env,
+        lender: Address,
+        status: Option<EscrowStatus>,
+        cursor: u32,
+        limit: u32,
+    ) -> EscrowPage {
+        list_indexed(&env, EscrowKey::LenderLoans(lender), status, cursor, limit)
+    }
+
+    
     pub fn is_past_due(env: Env, loan_id: u64) -> bool {
         let escrow: EscrowData = match env.storage().persistent().get(&EscrowKey::Escrow(loan_id)) {
             Some(e) => e,
@@ -243,6 +2125,21 @@ impl EscrowContract {
     }
 
     
+    pub fn get_health_factor(env: Env, loan_id: u64) -> Result<i128, EscrowError> {
+        let escrow = read_escrow(&env, loan_id)?;
+        health_factor(&env, &escrow)?.ok_or(EscrowError::OracleNotConfigured)
+    }
+
+    
+    pub fn get_collateral_basket(
+        env: Env,
+        loan_id: u64,
+    ) -> Result<Map<Address, i128>, EscrowError> {
+        let escrow = read_escrow(&env, loan_id)?;
+        Ok(collateral_basket(&escrow))
+    }
+
+    
     pub fn get_total_locked(env: Env) -> i128 {
         env.storage().persistent()
             .get(&EscrowKey::TotalLocked)
@@ -250,21 +2147,411 @@ impl EscrowContract {
     }
 
     
-    pub fn update_admin(env: Env, new_admin: Address) {
-        let current_admin: Address = env.storage().persistent()
-            .get(&EscrowKey::Admin)
-            .expect("Contract not initialized");
+    pub fn get_total_locked_by_token(env: Env, token: Address) -> i128 {
+        env.storage().persistent()
+            .get(&EscrowKey::TotalLockedByToken(token))
+            .unwrap_or(0)
+    }
+
+    
+    // Compares the recorded total for `token` with what the contract actually
+    // holds. A balance above the recorded total (e.g. a direct donation) is
+    // still solvent; anything below it means the accounting has drifted.
+    pub fn verify_solvency(env: Env, token: Address) -> SolvencyReport {
+        let recorded_total: i128 = env.storage().persistent()
+            .get(&EscrowKey::TotalLockedByToken(token.clone()))
+            .unwrap_or(0);
+        let contract_balance = token::Client::new(&env, &token)
+            .balance(&env.current_contract_address());
+
+        SolvencyReport {
+            token,
+            recorded_total,
+            contract_balance,
+            is_solvent: contract_balance >= recorded_total,
+        }
+    }
+
+    
+    // Extends the TTL of the given escrows (and the contract's own settings)
+    // so long-running loans are not archived between touches. Unknown or
+    // already-archived ids are skipped; returns how many were extended.
+    pub fn bump_escrows(
+        env: Env,
+        caller: Address,
+        loan_ids: Vec<u64>,
+    ) -> Result<u32, EscrowError> {
+        require_admin_or_keeper(&env, &caller)?;
+
+        env.storage().instance().extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+        extend_config_ttl(&env);
+
+        let mut bumped = 0;
+        for loan_id in loan_ids.iter() {
+            let escrow: Option<EscrowData> = env.storage().persistent().get(&EscrowKey::Escrow(loan_id));
+            if let Some(escrow) = escrow {
+                extend_escrow_ttl(&env, loan_id);
+                extend_loan_refs_ttl(&env, &escrow);
+                bumped += 1;
+            }
+        }
+
+        Ok(bumped)
+    }
+
+    
+    // Same as `bump_escrows` for the deal, milestone and HTLC escrows, which
+    // have their own id spaces. Returns how many entries were extended.
+    pub fn bump_agreements(
+        env: Env,
+        caller: Address,
+        deal_ids: Vec<u64>,
+        milestone_ids: Vec<u64>,
+        htlc_ids: Vec<u64>,
+    ) -> Result<u32, EscrowError> {
+        require_admin_or_keeper(&env, &caller)?;
+
+        env.storage().instance().extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+        extend_config_ttl(&env);
+
+        let keys = deal_ids.iter().map(EscrowKey::Deal)
+            .chain(milestone_ids.iter().map(EscrowKey::MilestoneEscrow))
+            .chain(htlc_ids.iter().map(EscrowKey::Htlc));
+
+        let mut bumped = 0;
+        for key in keys {
+            if env.storage().persistent().has(&key) {
+                extend_persistent_ttl(&env, &key);
+                bumped += 1;
+            }
+        }
+
+        Ok(bumped)
+    }
+
+    
+    pub fn set_keeper(env: Env, keeper: Address) -> Result<(), EscrowError> {
+        authorize_admin_action(&env, AdminAction::SetKeeper(keeper.clone()))?;
+
+        env.storage().persistent().set(&EscrowKey::Keeper, &keeper);
+        extend_persistent_ttl(&env, &EscrowKey::Keeper);
+
+        env.events().publish(
+            (Symbol::new(&env, "KeeperUpdated"),),
+            keeper,
+        );
+
+        Ok(())
+    }
+
+    
+    pub fn get_keeper(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&EscrowKey::Keeper)
+    }
+
+    
+    // First half of an admin handover: the new admin only takes over once
+    // they call `accept_admin` within `valid_for` seconds. A new proposal
+    // replaces any pending one.
+    pub fn propose_admin(env: Env, new_admin: Address, valid_for: u64) -> Result<(), EscrowError> {
+        authorize_admin_action(&env, AdminAction::ProposeAdmin(new_admin.clone(), valid_for))?;
+
+        let expires_at = env.ledger().timestamp()
+            .checked_add(valid_for)
+            .ok_or(EscrowError::InvalidConfig)?;
+        let pending = PendingAdmin {
+            admin: new_admin.clone(),
+            expires_at,
+        };
+        env.storage().persistent().set(&EscrowKey::PendingAdmin, &pending);
+
+        env.events().publish(
+            (Symbol::new(&env, "AdminProposed"),),
+            (new_admin, pending.expires_at),
+        );
+
+        Ok(())
+    }
+
+    
+    pub fn accept_admin(env: Env) -> Result<(), EscrowError> {
+        let pending: PendingAdmin = env.storage().persistent()
+            .get(&EscrowKey::PendingAdmin)
+            .ok_or(EscrowError::NoPendingAdmin)?;
+
+        if env.ledger().timestamp() > pending.expires_at {
+            return Err(EscrowError::HandoverExpired);
+        }
+
+        pending.admin.require_auth();
+
+        let previous = read_admin(&env)?;
+        env.storage().persistent().set(&EscrowKey::Admin, &pending.admin);
+        env.storage().persistent().remove(&EscrowKey::PendingAdmin);
+
+        env.events().publish(
+            (Symbol::new(&env, "AdminAccepted"),),
+            (previous, pending.admin),
+        );
+
+        Ok(())
+    }
+
+    
+    pub fn get_pending_admin(env: Env) -> Option<PendingAdmin> {
+        env.storage().persistent().get(&EscrowKey::PendingAdmin)
+    }
+
+    
+    // Installs, replaces or (with an empty list) removes the signer set.
+    // While a set is active, changing it needs the current signers' approval.
+    pub fn set_signer_set(
+        env: Env,
+        signers: Vec<Address>,
+        threshold: u32,
+    ) -> Result<(), EscrowError> {
+        authorize_admin_action(&env, AdminAction::SetSignerSet(signers.clone(), threshold))?;
+
+        if signers.is_empty() {
+            env.storage().persistent().remove(&EscrowKey::SignerSet);
+        } else {
+            if threshold == 0 || threshold > signers.len() {
+                return Err(EscrowError::InvalidConfig);
+            }
+            for (i, signer) in signers.iter().enumerate() {
+                if signers.first_index_of(&signer) != Some(i as u32) {
+                    return Err(EscrowError::InvalidConfig);
+                }
+            }
+
+            let set = SignerSet { signers: signers.clone(), threshold };
+            env.storage().persistent().set(&EscrowKey::SignerSet, &set);
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "SignerSetUpdated"),),
+            (signers, threshold),
+        );
+
+        Ok(())
+    }
+
+    
+    pub fn approve_action(
+        env: Env,
+        signer: Address,
+        action: AdminAction,
+    ) -> Result<u32, EscrowError> {
+        let signer_set: SignerSet = env.storage().persistent()
+            .get(&EscrowKey::SignerSet)
+            .ok_or(EscrowError::NotSigner)?;
+
+        if !signer_set.signers.contains(&signer) {
+            return Err(EscrowError::NotSigner);
+        }
+
+        signer.require_auth();
+
+        // Expired approvals are dropped here, so a signer can renew theirs.
+        let key = EscrowKey::Approvals(action.clone());
+        let mut approvals = live_approvals(&env, action.clone());
+
+        if approvals.contains_key(signer.clone()) {
+            return Err(EscrowError::AlreadyApproved);
+        }
+
+        approvals.set(signer.clone(), env.ledger().timestamp());
+        env.storage().persistent().set(&key, &approvals);
+
+        env.events().publish(
+            (Symbol::new(&env, "ActionApproved"), signer),
+            (action, approvals.len()),
+        );
+
+        Ok(approvals.len())
+    }
+
+    
+    pub fn get_signer_set(env: Env) -> Option<SignerSet> {
+        env.storage().persistent().get(&EscrowKey::SignerSet)
+    }
+
+    
+    pub fn get_approvals(env: Env, action: AdminAction) -> Vec<Address> {
+        live_approvals(&env, action).keys()
+    }
+
+    
+    pub fn set_min_collateral_ratio(env: Env, ratio_bps: u32) -> Result<(), EscrowError> {
+        authorize_admin_action(&env, AdminAction::SetMinCollateralRatio(ratio_bps))?;
+
+        env.storage().persistent().set(&EscrowKey::MinCollateralRatio, &ratio_bps);
+
+        env.events().publish(
+            (Symbol::new(&env, "MinCollateralRatioUpdated"),),
+            ratio_bps,
+        );
+
+        Ok(())
+    }
+
+    
+    pub fn get_min_collateral_ratio(env: Env) -> u32 {
+        min_collateral_ratio(&env)
+    }
+
+    
+    pub fn set_oracle(env: Env, oracle: Address, max_price_age: u64) -> Result<(), EscrowError> {
+        authorize_admin_action(&env, AdminAction::SetOracle(oracle.clone(), max_price_age))?;
+
+        let config = OracleConfig { oracle: oracle.clone(), max_price_age };
+        env.storage().persistent().set(&EscrowKey::Oracle, &config);
+
+        env.events().publish(
+            (Symbol::new(&env, "OracleUpdated"),),
+            (oracle, max_price_age),
+        );
+
+        Ok(())
+    }
+
+    
+    pub fn set_loan_token(env: Env, loan_token: Address) -> Result<(), EscrowError> {
+        authorize_admin_action(&env, AdminAction::SetLoanToken(loan_token.clone()))?;
+
+        env.storage().persistent().set(&EscrowKey::LoanToken, &loan_token);
+
+        Ok(())
+    }
+
+    
+    pub fn set_liquidation_threshold(
+        env: Env,
+        collateral_token: Address,
+        ltv_bps: u32,
+    ) -> Result<(), EscrowError> {
+        authorize_admin_action(&env, AdminAction::SetLiquidationThreshold(collateral_token.clone(), ltv_bps))?;
+
+        if ltv_bps == 0 || ltv_bps as i128 > BPS_DENOMINATOR {
+            return Err(EscrowError::InvalidConfig);
+        }
+
+        env.storage().persistent()
+            .set(&EscrowKey::LiquidationThreshold(collateral_token.clone()), &ltv_bps);
+        extend_persistent_ttl(&env, &EscrowKey::LiquidationThreshold(collateral_token.clone()));
+
+        env.events().publish(
+            (Symbol::new(&env, "LiquidationThresholdUpdated"),),
+            (collateral_token, ltv_bps),
+        );
+
+        Ok(())
+    }
+
+    
+    pub fn set_liquidation_config(
+        env: Env,
+        grace_period: u64,
+        liquidator_bonus_bps: u32,
+    ) -> Result<(), EscrowError> {
+        authorize_admin_action(&env, AdminAction::SetLiquidationConfig(grace_period, liquidator_bonus_bps))?;
+
+        if liquidator_bonus_bps as i128 > BPS_DENOMINATOR {
+            return Err(EscrowError::InvalidConfig);
+        }
+
+        let config = LiquidationConfig { grace_period, liquidator_bonus_bps };
+        env.storage().persistent().set(&EscrowKey::LiquidationConfig, &config);
+
+        env.events().publish(
+            (Symbol::new(&env, "LiquidationConfigUpdated"),),
+            (grace_period, liquidator_bonus_bps),
+        );
+
+        Ok(())
+    }
+
+    
+    pub fn get_liquidation_config(env: Env) -> LiquidationConfig {
+        liquidation_config(&env)
+    }
+
+    
+    pub fn set_collateral_haircut(
+        env: Env,
+        token: Address,
+        haircut_bps: u32,
+    ) -> Result<(), EscrowError> {
+        authorize_admin_action(&env, AdminAction::SetCollateralHaircut(token.clone(), haircut_bps))?;
+
+        if haircut_bps as i128 >= BPS_DENOMINATOR {
+            return Err(EscrowError::InvalidConfig);
+        }
+
+        env.storage().persistent().set(&EscrowKey::CollateralHaircut(token.clone()), &haircut_bps);
+        extend_persistent_ttl(&env, &EscrowKey::CollateralHaircut(token.clone()));
+
+        env.events().publish(
+            (Symbol::new(&env, "CollateralHaircutUpdated"),),
+            (token, haircut_bps),
+        );
 
-        current_admin.require_auth();
+        Ok(())
+    }
 
-        env.storage().persistent().set(&EscrowKey::Admin, &new_admin);
+    
+    pub fn get_collateral_haircut(env: Env, token: Address) -> u32 {
+        collateral_haircut(&env, &token)
     }
 
     
-    pub fn get_admin(env: Env) -> Address {
+    pub fn set_auction_config(
+        env: Env,
+        collateral_token: Address,
+        start_premium_bps: u32,
+        floor_bps: u32,
+        duration: u64,
+        penalty_bps: u32,
+    ) -> Result<(), EscrowError> {
+        authorize_admin_action(&env, AdminAction::SetAuctionConfig(
+            collateral_token.clone(), start_premium_bps, floor_bps, duration, penalty_bps,
+        ))?;
+
+        if floor_bps == 0
+            || floor_bps > start_premium_bps
+            || floor_bps > BPS_DENOMINATOR as u32
+            || duration == 0
+            || penalty_bps > MAX_LIQUIDATION_PENALTY_BPS
+        {
+            return Err(EscrowError::InvalidConfig);
+        }
+
+        let config = AuctionConfig { start_premium_bps, floor_bps, duration, penalty_bps };
         env.storage().persistent()
-            .get(&EscrowKey::Admin)
-            .expect("Contract not initialized")
+            .set(&EscrowKey::AuctionConfig(collateral_token.clone()), &config);
+        extend_persistent_ttl(&env, &EscrowKey::AuctionConfig(collateral_token.clone()));
+
+        env.events().publish(
+            (Symbol::new(&env, "AuctionConfigUpdated"),),
+            (collateral_token, start_premium_bps, floor_bps, duration, penalty_bps),
+        );
+
+        Ok(())
+    }
+
+    
+    pub fn get_auction_config(env: Env, collateral_token: Address) -> Option<AuctionConfig> {
+        env.storage().persistent().get(&EscrowKey::AuctionConfig(collateral_token))
+    }
+
+    
+    pub fn get_oracle(env: Env) -> Option<OracleConfig> {
+        env.storage().persistent().get(&EscrowKey::Oracle)
+    }
+
+    
+    pub fn get_admin(env: Env) -> Result<Address, EscrowError> {
+        read_admin(&env)
     }
 }
 
@@ -5,13 +5,34 @@
 
 #[cfg(test)]
 mod escrow_tests {
-    use crate::escrow::{EscrowContract, EscrowContractClient, EscrowData};
+    use crate::escrow::{
+        AdminAction, DealStatus, EscrowContract, EscrowContractClient, EscrowData, EscrowError, EscrowStatus,
+        HtlcStatus, InterestMode, MilestoneStatus, PriceData,
+    };
     use soroban_sdk::{
+        contract, contractimpl,
         testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Ledger, LedgerInfo},
         token::{self, Client as TokenClient, StellarAssetClient},
-        Address, Env, IntoVal, Symbol,
+        vec, Address, Bytes, BytesN, Env, IntoVal, Symbol,
     };
 
+    // ============== Mock Price Oracle ==============
+
+    #[contract]
+    pub struct MockOracle;
+
+    #[contractimpl]
+    impl MockOracle {
+        pub fn set_price(env: Env, asset: Address, price: i128) {
+            let data = PriceData { price, timestamp: env.ledger().timestamp() };
+            env.storage().persistent().set(&asset, &data);
+        }
+
+        pub fn lastprice(env: Env, asset: Address) -> Option<PriceData> {
+            env.storage().persistent().get(&asset)
+        }
+    }
+
     // ============== Helper Functions ==============
 
     fn create_token_contract<'a>(
@@ -42,6 +63,42 @@ mod escrow_tests {
         (env, admin, borrower, lender, client)
     }
 
+    fn setup_with_token() -> (
+        Env,
+        Address,
+        Address,
+        Address,
+        Address,
+        TokenClient<'static>,
+        EscrowContractClient<'static>,
+    ) {
+        let (env, admin, borrower, lender, client) = setup_test_env();
+
+        let (token_address, token_client, token_admin) = create_token_contract(&env, &admin);
+        token_admin.mint(&borrower, &100000);
+
+        (env, admin, borrower, lender, token_address, token_client, client)
+    }
+
+    fn setup_oracle(
+        env: &Env,
+        client: &EscrowContractClient,
+        collateral_token: &Address,
+        loan_token: &Address,
+    ) -> MockOracleClient<'static> {
+        let oracle_id = env.register_contract(None, MockOracle);
+        let oracle = MockOracleClient::new(env, &oracle_id);
+
+        oracle.set_price(collateral_token, &100);
+        oracle.set_price(loan_token, &100);
+
+        client.set_oracle(&oracle_id, &3600);
+        client.set_loan_token(loan_token);
+        client.set_liquidation_threshold(collateral_token, &8000);
+
+        oracle
+    }
+
     // ============== Initialization Tests ==============
 
     #[test]
@@ -60,7 +117,6 @@ mod escrow_tests {
     }
 
     #[test]
-    #[should_panic(expected = "Contract already initialized")]
     fn test_initialize_twice_fails() {
         let env = Env::default();
         env.mock_all_auths();
@@ -70,8 +126,8 @@ mod escrow_tests {
         let client = EscrowContractClient::new(&env, &contract_id);
 
         client.initialize(&admin);
-        // Second initialization should panic
-        client.initialize(&admin);
+        // Second initialization should fail
+        assert_eq!(client.try_initialize(&admin), Err(Ok(EscrowError::AlreadyInitialized)));
     }
 
     // ============== Admin Tests ==============
@@ -81,9 +137,16 @@ mod escrow_tests {
         let (env, admin, _, _, client) = setup_test_env();
 
         let new_admin = Address::generate(&env);
-        client.update_admin(&new_admin);
+        client.propose_admin(&new_admin, &3600);
+
+        // Nothing changes until the new admin accepts
+        assert_eq!(client.get_admin(), admin);
+        assert_eq!(client.get_pending_admin().unwrap().admin, new_admin);
+
+        client.accept_admin();
 
         assert_eq!(client.get_admin(), new_admin);
+        assert!(client.get_pending_admin().is_none());
     }
 
     #[test]
@@ -120,7 +183,7 @@ mod escrow_tests {
         let loan_amount: i128 = 3000;
         let duration: u64 = 86400 * 30; // 30 days
 
-        let result = client.lock_collateral(
+        client.lock_collateral(
             &loan_id,
             &borrower,
             &lender,
@@ -130,7 +193,6 @@ mod escrow_tests {
             &duration,
         );
 
-        assert!(result);
         assert_eq!(client.get_total_locked(), collateral_amount);
 
         // Check escrow data
@@ -159,7 +221,7 @@ mod escrow_tests {
         client.initialize(&admin);
 
         // Try to lock with zero collateral
-        let result = client.lock_collateral(
+        let result = client.try_lock_collateral(
             &1,
             &borrower,
             &lender,
@@ -169,7 +231,7 @@ mod escrow_tests {
             &86400,
         );
 
-        assert!(!result);
+        assert_eq!(result, Err(Ok(EscrowError::InvalidAmount)));
     }
 
     #[test]
@@ -189,7 +251,7 @@ mod escrow_tests {
         client.initialize(&admin);
 
         // First lock succeeds
-        let result1 = client.lock_collateral(
+        client.lock_collateral(
             &1,
             &borrower,
             &lender,
@@ -198,10 +260,9 @@ mod escrow_tests {
             &3000,
             &86400,
         );
-        assert!(result1);
 
         // Second lock with same ID fails
-        let result2 = client.lock_collateral(
+        let result2 = client.try_lock_collateral(
             &1, // Same loan_id
             &borrower,
             &lender,
@@ -210,7 +271,7 @@ mod escrow_tests {
             &3000,
             &86400,
         );
-        assert!(!result2);
+        assert_eq!(result2, Err(Ok(EscrowError::EscrowExists)));
     }
 
     // ============== Release Collateral Tests ==============
@@ -246,8 +307,7 @@ mod escrow_tests {
         let borrower_balance_before = token_client.balance(&borrower);
 
         // Release collateral
-        let result = client.release_collateral(&1);
-        assert!(result);
+        client.release_collateral(&1);
 
         // Check borrower got collateral back
         let borrower_balance_after = token_client.balance(&borrower);
@@ -266,8 +326,8 @@ mod escrow_tests {
     fn test_release_nonexistent_escrow() {
         let (_env, _admin, _, _, client) = setup_test_env();
 
-        let result = client.release_collateral(&999);
-        assert!(!result);
+        let result = client.try_release_collateral(&999);
+        assert_eq!(result, Err(Ok(EscrowError::EscrowNotFound)));
     }
 
     #[test]
@@ -291,8 +351,8 @@ mod escrow_tests {
         client.release_collateral(&1);
 
         // Try to release again
-        let result = client.release_collateral(&1);
-        assert!(!result);
+        let result = client.try_release_collateral(&1);
+        assert_eq!(result, Err(Ok(EscrowError::AlreadyReleased)));
     }
 
     // ============== Liquidation Tests ==============
@@ -318,8 +378,7 @@ mod escrow_tests {
 
         // Liquidate
         let lender_balance_before = token_client.balance(&lender);
-        let result = client.liquidate(&1);
-        assert!(result);
+        client.liquidate(&1);
 
         // Check lender received collateral
         let lender_balance_after = token_client.balance(&lender);
@@ -335,8 +394,8 @@ mod escrow_tests {
     fn test_liquidate_nonexistent() {
         let (_env, _admin, _, _, client) = setup_test_env();
 
-        let result = client.liquidate(&999);
-        assert!(!result);
+        let result = client.try_liquidate(&999);
+        assert_eq!(result, Err(Ok(EscrowError::EscrowNotFound)));
     }
 
     #[test]
@@ -359,8 +418,8 @@ mod escrow_tests {
         client.liquidate(&1);
 
         // Try to liquidate again
-        let result = client.liquidate(&1);
-        assert!(!result);
+        let result = client.try_liquidate(&1);
+        assert_eq!(result, Err(Ok(EscrowError::AlreadyLiquidated)));
     }
 
     // ============== Query Tests ==============
@@ -460,4 +519,1384 @@ mod escrow_tests {
         client.liquidate(&1);
         assert_eq!(client.get_total_locked(), 7000);
     }
+
+    // ============== Collateral Adjustment Tests ==============
+
+    #[test]
+    fn test_add_collateral() {
+        let (_env, _admin, borrower, lender, token_address, token_client, client) = setup_with_token();
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &86400);
+        client.add_collateral(&1, &2000);
+
+        let escrow = client.get_escrow(&1).unwrap();
+        assert_eq!(escrow.collateral_amount, 7000);
+        assert_eq!(client.get_total_locked(), 7000);
+        assert_eq!(token_client.balance(&borrower), 93000);
+    }
+
+    #[test]
+    fn test_add_collateral_to_released_escrow_fails() {
+        let (_env, _admin, borrower, lender, token_address, _, client) = setup_with_token();
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &86400);
+        client.release_collateral(&1);
+
+        assert_eq!(client.try_add_collateral(&1, &1000), Err(Ok(EscrowError::AlreadyReleased)));
+        assert_eq!(client.try_add_collateral(&999, &1000), Err(Ok(EscrowError::EscrowNotFound)));
+    }
+
+    #[test]
+    fn test_withdraw_collateral_within_ratio() {
+        let (_env, _admin, borrower, lender, token_address, token_client, client) = setup_with_token();
+
+        // 150% of 3000 is 4500, so 1500 of the 6000 locked is excess
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &6000, &3000, &86400);
+        client.withdraw_collateral(&1, &1500);
+
+        let escrow = client.get_escrow(&1).unwrap();
+        assert_eq!(escrow.collateral_amount, 4500);
+        assert_eq!(client.get_total_locked(), 4500);
+        assert_eq!(token_client.balance(&borrower), 95500);
+    }
+
+    #[test]
+    fn test_withdraw_collateral_below_ratio_fails() {
+        let (_env, _admin, borrower, lender, token_address, _, client) = setup_with_token();
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &6000, &3000, &86400);
+        assert_eq!(
+            client.try_withdraw_collateral(&1, &1501),
+            Err(Ok(EscrowError::CollateralRatioTooLow)),
+        );
+        assert_eq!(
+            client.try_withdraw_collateral(&1, &7000),
+            Err(Ok(EscrowError::InsufficientCollateral)),
+        );
+
+        assert_eq!(client.get_escrow(&1).unwrap().collateral_amount, 6000);
+    }
+
+    #[test]
+    fn test_set_min_collateral_ratio() {
+        let (_env, _admin, borrower, lender, token_address, _, client) = setup_with_token();
+
+        assert_eq!(client.get_min_collateral_ratio(), 15000);
+        client.set_min_collateral_ratio(&12000);
+        assert_eq!(client.get_min_collateral_ratio(), 12000);
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &6000, &3000, &86400);
+        client.withdraw_collateral(&1, &2400);
+        assert_eq!(client.get_escrow(&1).unwrap().collateral_amount, 3600);
+    }
+
+    // ============== Oracle LTV Tests ==============
+
+    #[test]
+    fn test_health_factor() {
+        let (env, _admin, borrower, lender, token_address, _, client) = setup_with_token();
+        let loan_token = Address::generate(&env);
+        let oracle = setup_oracle(&env, &client, &token_address, &loan_token);
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &86400);
+
+        // 5000 * 80% / 3000
+        assert_eq!(client.get_health_factor(&1), 13333);
+
+        oracle.set_price(&token_address, &50);
+        assert_eq!(client.get_health_factor(&1), 6666);
+    }
+
+    #[test]
+    fn test_liquidate_healthy_position_fails() {
+        let (env, _admin, borrower, lender, token_address, _, client) = setup_with_token();
+        let loan_token = Address::generate(&env);
+        setup_oracle(&env, &client, &token_address, &loan_token);
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &86400);
+
+        assert_eq!(client.try_liquidate(&1), Err(Ok(EscrowError::PositionHealthy)));
+        assert!(client.get_escrow(&1).unwrap().is_locked);
+    }
+
+    #[test]
+    fn test_liquidate_unhealthy_position() {
+        let (env, _admin, borrower, lender, token_address, token_client, client) = setup_with_token();
+        let loan_token = Address::generate(&env);
+        let oracle = setup_oracle(&env, &client, &token_address, &loan_token);
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &86400);
+        oracle.set_price(&token_address, &70);
+
+        assert!(client.get_health_factor(&1) < 10000);
+        client.liquidate(&1);
+        assert_eq!(token_client.balance(&lender), 5000);
+    }
+
+    #[test]
+    fn test_stale_oracle_price_rejected() {
+        let (env, _admin, borrower, lender, token_address, _, client) = setup_with_token();
+        let loan_token = Address::generate(&env);
+        setup_oracle(&env, &client, &token_address, &loan_token);
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &86400);
+
+        env.ledger().with_mut(|li| li.timestamp += 3601);
+        assert_eq!(client.try_liquidate(&1), Err(Ok(EscrowError::StaleOraclePrice)));
+    }
+
+    #[test]
+    fn test_unbounded_price_age_does_not_overflow() {
+        let (env, _admin, borrower, lender, token_address, _, client) = setup_with_token();
+        let loan_token = Address::generate(&env);
+        env.ledger().with_mut(|li| li.timestamp = 1000);
+        let oracle = setup_oracle(&env, &client, &token_address, &loan_token);
+        client.set_oracle(&client.get_oracle().unwrap().oracle, &u64::MAX);
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &86400);
+        oracle.set_price(&token_address, &70);
+
+        assert!(client.get_health_factor(&1) < 10000);
+    }
+
+    #[test]
+    fn test_withdraw_collateral_uses_oracle_value() {
+        let (env, _admin, borrower, lender, token_address, _, client) = setup_with_token();
+        let loan_token = Address::generate(&env);
+        let oracle = setup_oracle(&env, &client, &token_address, &loan_token);
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &6000, &3000, &86400);
+
+        // Collateral worth twice as much as the loan token halves the units required
+        oracle.set_price(&token_address, &200);
+        client.withdraw_collateral(&1, &3750);
+        assert_eq!(client.get_escrow(&1).unwrap().collateral_amount, 2250);
+    }
+
+    // ============== Permissionless Liquidation Tests ==============
+
+    #[test]
+    fn test_liquidate_by_after_grace_period() {
+        let (env, _admin, borrower, lender, token_address, token_client, client) = setup_with_token();
+        let liquidator = Address::generate(&env);
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &86400);
+
+        env.ledger().with_mut(|li| li.timestamp += 86400 * 2 + 1);
+        client.liquidate_by(&1, &liquidator);
+
+        // Default bonus is 5%
+        assert_eq!(token_client.balance(&liquidator), 250);
+        assert_eq!(token_client.balance(&lender), 4750);
+        assert!(client.get_escrow(&1).unwrap().is_liquidated);
+        assert_eq!(client.get_total_locked(), 0);
+    }
+
+    #[test]
+    fn test_liquidate_by_within_grace_period_fails() {
+        let (env, _admin, borrower, lender, token_address, _, client) = setup_with_token();
+        let liquidator = Address::generate(&env);
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &86400);
+
+        assert_eq!(client.try_liquidate_by(&1, &liquidator), Err(Ok(EscrowError::NotLiquidatable)));
+
+        // Past due but still inside the one-day grace period
+        env.ledger().with_mut(|li| li.timestamp += 86400 + 3600);
+        assert!(client.is_past_due(&1));
+        assert_eq!(client.try_liquidate_by(&1, &liquidator), Err(Ok(EscrowError::NotLiquidatable)));
+    }
+
+    #[test]
+    fn test_liquidate_by_undercollateralised_before_due() {
+        let (env, _admin, borrower, lender, token_address, token_client, client) = setup_with_token();
+        let loan_token = Address::generate(&env);
+        let oracle = setup_oracle(&env, &client, &token_address, &loan_token);
+        let liquidator = Address::generate(&env);
+
+        client.set_liquidation_config(&3600, &1000);
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &86400);
+
+        assert_eq!(client.try_liquidate_by(&1, &liquidator), Err(Ok(EscrowError::NotLiquidatable)));
+
+        oracle.set_price(&token_address, &60);
+        client.liquidate_by(&1, &liquidator);
+        assert_eq!(token_client.balance(&liquidator), 500);
+        assert_eq!(token_client.balance(&lender), 4500);
+    }
+
+    // ============== Collateral Basket Tests ==============
+
+    #[test]
+    fn test_add_basket_collateral() {
+        let (env, admin, borrower, lender, token_address, _, client) = setup_with_token();
+        let (usdc, usdc_client, usdc_admin) = create_token_contract(&env, &admin);
+        usdc_admin.mint(&borrower, &10000);
+
+        client.set_collateral_haircut(&usdc, &1000);
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &86400);
+        client.add_collateral_token(&1, &usdc, &2000);
+
+        let basket = client.get_collateral_basket(&1);
+        assert_eq!(basket.len(), 2);
+        assert_eq!(basket.get(token_address).unwrap(), 5000);
+        assert_eq!(basket.get(usdc.clone()).unwrap(), 2000);
+        assert_eq!(client.get_total_locked(), 7000);
+        assert_eq!(usdc_client.balance(&borrower), 8000);
+    }
+
+    #[test]
+    fn test_add_unaccepted_basket_token_fails() {
+        let (env, admin, borrower, lender, token_address, _, client) = setup_with_token();
+        let (other, _, other_admin) = create_token_contract(&env, &admin);
+        other_admin.mint(&borrower, &10000);
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &86400);
+        assert_eq!(
+            client.try_add_collateral_token(&1, &other, &2000),
+            Err(Ok(EscrowError::TokenNotAccepted)),
+        );
+    }
+
+    #[test]
+    fn test_release_returns_whole_basket() {
+        let (env, admin, borrower, lender, token_address, token_client, client) = setup_with_token();
+        let (usdc, usdc_client, usdc_admin) = create_token_contract(&env, &admin);
+        usdc_admin.mint(&borrower, &10000);
+
+        client.set_collateral_haircut(&usdc, &0);
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &86400);
+        client.add_collateral_token(&1, &usdc, &2000);
+
+        client.release_collateral(&1);
+        assert_eq!(token_client.balance(&borrower), 100000);
+        assert_eq!(usdc_client.balance(&borrower), 10000);
+        assert_eq!(client.get_total_locked(), 0);
+    }
+
+    #[test]
+    fn test_liquidate_transfers_whole_basket() {
+        let (env, admin, borrower, lender, token_address, token_client, client) = setup_with_token();
+        let (usdc, usdc_client, usdc_admin) = create_token_contract(&env, &admin);
+        usdc_admin.mint(&borrower, &10000);
+
+        client.set_collateral_haircut(&usdc, &0);
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &86400);
+        client.add_collateral_token(&1, &usdc, &2000);
+
+        client.liquidate(&1);
+        assert_eq!(token_client.balance(&lender), 5000);
+        assert_eq!(usdc_client.balance(&lender), 2000);
+        assert_eq!(client.get_total_locked(), 0);
+    }
+
+    #[test]
+    fn test_withdraw_applies_haircuts() {
+        let (env, admin, borrower, lender, token_address, _, client) = setup_with_token();
+        let (usdc, _, usdc_admin) = create_token_contract(&env, &admin);
+        usdc_admin.mint(&borrower, &10000);
+
+        client.set_collateral_haircut(&usdc, &5000);
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &4500, &3000, &86400);
+        client.add_collateral_token(&1, &usdc, &2000);
+
+        // The 2000 USDC only counts for 1000, so 1000 of the primary can go
+        // but not a unit more.
+        assert_eq!(
+            client.try_withdraw_collateral(&1, &1001),
+            Err(Ok(EscrowError::CollateralRatioTooLow)),
+        );
+        client.withdraw_collateral(&1, &1000);
+
+        // All of the USDC can come out only if the primary still covers 150%.
+        assert_eq!(
+            client.try_withdraw_collateral_token(&1, &usdc, &2000),
+            Err(Ok(EscrowError::CollateralRatioTooLow)),
+        );
+    }
+
+    // ============== P2P Deal Tests ==============
+
+    #[test]
+    fn test_deal_confirm_delivery() {
+        let (env, _admin, buyer, _, token_address, token_client, client) = setup_with_token();
+        let seller = Address::generate(&env);
+
+        let deal_id = client.create_deal(&buyer, &seller, &None, &token_address, &4000, &86400);
+        assert_eq!(deal_id, 1);
+        assert_eq!(client.get_total_locked(), 4000);
+
+        client.mark_delivered(&deal_id);
+        client.confirm_delivery(&deal_id);
+
+        assert_eq!(token_client.balance(&seller), 4000);
+        assert_eq!(client.get_deal(&deal_id).unwrap().status, DealStatus::Completed);
+        assert_eq!(client.get_total_locked(), 0);
+
+        // Settled deals cannot be paid out twice
+        assert_eq!(client.try_confirm_delivery(&deal_id), Err(Ok(EscrowError::InvalidDealState)));
+    }
+
+    #[test]
+    fn test_deal_dispute_split() {
+        let (env, _admin, buyer, _, token_address, token_client, client) = setup_with_token();
+        let seller = Address::generate(&env);
+        let arbiter = Address::generate(&env);
+
+        let deal_id = client.create_deal(
+            &buyer, &seller, &Some(arbiter.clone()), &token_address, &4000, &86400,
+        );
+        client.mark_delivered(&deal_id);
+
+        client.open_dispute(&deal_id);
+        assert_eq!(client.try_confirm_delivery(&deal_id), Err(Ok(EscrowError::InvalidDealState)));
+        assert_eq!(client.try_claim_auto_release(&deal_id), Err(Ok(EscrowError::InvalidDealState)));
+
+        assert_eq!(client.try_resolve_dispute(&deal_id, &4001), Err(Ok(EscrowError::InvalidSplit)));
+        client.resolve_dispute(&deal_id, &1500);
+
+        assert_eq!(token_client.balance(&seller), 1500);
+        assert_eq!(token_client.balance(&buyer), 100000 - 1500);
+        assert_eq!(client.get_deal(&deal_id).unwrap().status, DealStatus::Resolved);
+    }
+
+    #[test]
+    fn test_deal_dispute_requires_arbiter() {
+        let (env, _admin, buyer, _, token_address, _, client) = setup_with_token();
+        let seller = Address::generate(&env);
+
+        let deal_id = client.create_deal(&buyer, &seller, &None, &token_address, &4000, &86400);
+        assert_eq!(client.try_open_dispute(&deal_id), Err(Ok(EscrowError::NoArbiter)));
+    }
+
+    #[test]
+    fn test_deal_auto_release_after_timeout() {
+        let (env, _admin, buyer, _, token_address, token_client, client) = setup_with_token();
+        let seller = Address::generate(&env);
+
+        let deal_id = client.create_deal(&buyer, &seller, &None, &token_address, &4000, &86400);
+
+        // Nothing to auto-release before delivery
+        assert_eq!(client.try_claim_auto_release(&deal_id), Err(Ok(EscrowError::InvalidDealState)));
+
+        client.mark_delivered(&deal_id);
+        env.ledger().with_mut(|li| li.timestamp += 86400);
+        assert_eq!(
+            client.try_claim_auto_release(&deal_id),
+            Err(Ok(EscrowError::TimeoutNotReached)),
+        );
+
+        env.ledger().with_mut(|li| li.timestamp += 1);
+        client.claim_auto_release(&deal_id);
+        assert_eq!(token_client.balance(&seller), 4000);
+    }
+
+    #[test]
+    fn test_deal_refund_undelivered_after_timeout() {
+        let (env, _admin, buyer, _, token_address, token_client, client) = setup_with_token();
+        let seller = Address::generate(&env);
+
+        let deal_id = client.create_deal(&buyer, &seller, &None, &token_address, &4000, &86400);
+
+        env.ledger().with_mut(|li| li.timestamp += 86400);
+        assert_eq!(
+            client.try_refund_undelivered(&deal_id),
+            Err(Ok(EscrowError::TimeoutNotReached)),
+        );
+
+        env.ledger().with_mut(|li| li.timestamp += 1);
+        client.refund_undelivered(&deal_id);
+
+        assert_eq!(token_client.balance(&buyer), 100000);
+        assert_eq!(client.get_deal(&deal_id).unwrap().status, DealStatus::Refunded);
+        assert_eq!(client.get_total_locked(), 0);
+
+        // A refunded deal can no longer be delivered or paid out
+        assert_eq!(client.try_mark_delivered(&deal_id), Err(Ok(EscrowError::InvalidDealState)));
+    }
+
+    #[test]
+    fn test_deal_refund_undelivered_rejected_after_delivery() {
+        let (env, _admin, buyer, _, token_address, _, client) = setup_with_token();
+        let seller = Address::generate(&env);
+
+        let deal_id = client.create_deal(&buyer, &seller, &None, &token_address, &4000, &86400);
+        client.mark_delivered(&deal_id);
+
+        env.ledger().with_mut(|li| li.timestamp += 86400 + 1);
+        assert_eq!(
+            client.try_refund_undelivered(&deal_id),
+            Err(Ok(EscrowError::InvalidDealState)),
+        );
+    }
+
+    // ============== Milestone Escrow Tests ==============
+
+    fn create_milestones(
+        env: &Env,
+        client: &EscrowContractClient,
+        funder: &Address,
+        payee: &Address,
+        arbiter: &Option<Address>,
+        token: &Address,
+    ) -> u64 {
+        let now = env.ledger().timestamp();
+        client.create_milestone_escrow(
+            funder,
+            payee,
+            arbiter,
+            token,
+            &vec![env, 1000i128, 2000i128, 3000i128],
+            &vec![env, now + 86400, now + 86400 * 2, now + 86400 * 3],
+        )
+    }
+
+    #[test]
+    fn test_milestone_claim_and_approve() {
+        let (env, _admin, funder, _, token_address, token_client, client) = setup_with_token();
+        let payee = Address::generate(&env);
+
+        let escrow_id = create_milestones(&env, &client, &funder, &payee, &None, &token_address);
+        assert_eq!(client.get_total_locked(), 6000);
+
+        // Milestones are claimed in order
+        assert_eq!(
+            client.try_claim_milestone(&escrow_id, &1),
+            Err(Ok(EscrowError::MilestoneOutOfOrder)),
+        );
+        client.claim_milestone(&escrow_id, &0);
+        client.approve_milestone(&escrow_id, &0);
+        assert_eq!(
+            client.try_approve_milestone(&escrow_id, &0),
+            Err(Ok(EscrowError::InvalidMilestoneState)),
+        );
+
+        assert_eq!(token_client.balance(&payee), 1000);
+        assert_eq!(client.get_total_locked(), 5000);
+
+        let escrow = client.get_milestone_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.milestones.get(0).unwrap().status, MilestoneStatus::Released);
+        assert_eq!(escrow.milestones.get(1).unwrap().status, MilestoneStatus::Pending);
+    }
+
+    #[test]
+    fn test_milestone_dispute_resolution() {
+        let (env, _admin, funder, _, token_address, token_client, client) = setup_with_token();
+        let payee = Address::generate(&env);
+        let arbiter = Some(Address::generate(&env));
+
+        let escrow_id = create_milestones(&env, &client, &funder, &payee, &arbiter, &token_address);
+
+        client.claim_milestone(&escrow_id, &0);
+        client.dispute_milestone(&escrow_id, &0);
+        client.resolve_milestone(&escrow_id, &0, &true);
+        assert_eq!(token_client.balance(&payee), 1000);
+
+        client.claim_milestone(&escrow_id, &1);
+        client.dispute_milestone(&escrow_id, &1);
+        client.resolve_milestone(&escrow_id, &1, &false);
+        assert_eq!(token_client.balance(&payee), 1000);
+        assert_eq!(token_client.balance(&funder), 100000 - 6000 + 2000);
+
+        let escrow = client.get_milestone_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.milestones.get(1).unwrap().status, MilestoneStatus::Refunded);
+    }
+
+    #[test]
+    fn test_milestone_auto_release_when_funder_silent() {
+        let (env, _admin, funder, _, token_address, token_client, client) = setup_with_token();
+        let payee = Address::generate(&env);
+        let arbiter = Some(Address::generate(&env));
+
+        let escrow_id = create_milestones(&env, &client, &funder, &payee, &arbiter, &token_address);
+
+        assert_eq!(
+            client.try_claim_milestone_auto_release(&escrow_id, &0),
+            Err(Ok(EscrowError::InvalidMilestoneState)),
+        );
+
+        client.claim_milestone(&escrow_id, &0);
+
+        // The funder neither approves nor disputes within the review period
+        env.ledger().with_mut(|li| li.timestamp += 7 * 86400);
+        assert_eq!(
+            client.try_claim_milestone_auto_release(&escrow_id, &0),
+            Err(Ok(EscrowError::TimeoutNotReached)),
+        );
+
+        env.ledger().with_mut(|li| li.timestamp += 1);
+        client.claim_milestone_auto_release(&escrow_id, &0);
+        assert_eq!(token_client.balance(&payee), 1000);
+        assert_eq!(client.get_total_locked(), 5000);
+
+        let escrow = client.get_milestone_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.milestones.get(0).unwrap().status, MilestoneStatus::Released);
+        assert_eq!(
+            client.try_dispute_milestone(&escrow_id, &0),
+            Err(Ok(EscrowError::InvalidMilestoneState)),
+        );
+    }
+
+    #[test]
+    fn test_milestone_refund_after_deadline() {
+        let (env, _admin, funder, _, token_address, token_client, client) = setup_with_token();
+        let payee = Address::generate(&env);
+
+        let escrow_id = create_milestones(&env, &client, &funder, &payee, &None, &token_address);
+
+        assert_eq!(
+            client.try_refund_milestone(&escrow_id, &0),
+            Err(Ok(EscrowError::DeadlineNotReached)),
+        );
+
+        env.ledger().with_mut(|li| li.timestamp += 86400 + 1);
+        assert_eq!(
+            client.try_claim_milestone(&escrow_id, &0),
+            Err(Ok(EscrowError::DeadlinePassed)),
+        );
+        client.refund_milestone(&escrow_id, &0);
+        assert_eq!(token_client.balance(&funder), 100000 - 6000 + 1000);
+
+        // A refunded milestone no longer blocks the next claim
+        client.claim_milestone(&escrow_id, &1);
+    }
+
+    #[test]
+    fn test_milestone_deadlines_must_be_ordered() {
+        let (env, _admin, funder, _, token_address, _, client) = setup_with_token();
+        let payee = Address::generate(&env);
+        let now = env.ledger().timestamp();
+
+        let result = client.try_create_milestone_escrow(
+            &funder,
+            &payee,
+            &None,
+            &token_address,
+            &vec![&env, 1000i128, 2000i128],
+            &vec![&env, now + 86400 * 2, now + 86400],
+        );
+        assert_eq!(result, Err(Ok(EscrowError::InvalidMilestones)));
+    }
+
+    // ============== Per-Token Accounting Tests ==============
+
+    #[test]
+    fn test_total_locked_by_token() {
+        let (env, admin, borrower, lender, token_address, _, client) = setup_with_token();
+        let (usdc, _, usdc_admin) = create_token_contract(&env, &admin);
+        usdc_admin.mint(&borrower, &10000);
+
+        client.set_collateral_haircut(&usdc, &0);
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &86400);
+        client.add_collateral_token(&1, &usdc, &2000);
+        client.create_deal(&borrower, &lender, &None, &usdc, &1500, &86400);
+
+        assert_eq!(client.get_total_locked_by_token(&token_address), 5000);
+        assert_eq!(client.get_total_locked_by_token(&usdc), 3500);
+
+        client.release_collateral(&1);
+        assert_eq!(client.get_total_locked_by_token(&token_address), 0);
+        assert_eq!(client.get_total_locked_by_token(&usdc), 1500);
+    }
+
+    #[test]
+    fn test_verify_solvency() {
+        let (env, _admin, borrower, lender, token_address, _, client) = setup_with_token();
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &86400);
+
+        let report = client.verify_solvency(&token_address);
+        assert_eq!(report.recorded_total, 5000);
+        assert_eq!(report.contract_balance, 5000);
+        assert!(report.is_solvent);
+
+        // A direct transfer into the contract is surplus, not drift
+        let token_client = TokenClient::new(&env, &token_address);
+        token_client.transfer(&borrower, &client.address, &100);
+        let report = client.verify_solvency(&token_address);
+        assert_eq!(report.contract_balance, 5100);
+        assert!(report.is_solvent);
+
+        token_client.burn(&client.address, &200);
+        let report = client.verify_solvency(&token_address);
+        assert_eq!(report.contract_balance, 4900);
+        assert!(!report.is_solvent);
+    }
+
+    // ============== HTLC Tests ==============
+
+    fn hashlock(env: &Env, preimage: &Bytes) -> BytesN<32> {
+        env.crypto().sha256(preimage).to_bytes()
+    }
+
+    #[test]
+    fn test_htlc_claim_with_preimage() {
+        let (env, _admin, sender, _, token_address, token_client, client) = setup_with_token();
+        let recipient = Address::generate(&env);
+        let secret = Bytes::from_slice(&env, b"atomic swap secret");
+        let timelock = env.ledger().timestamp() + 3600;
+
+        let htlc_id = client.create_htlc(
+            &sender, &recipient, &token_address, &2500, &hashlock(&env, &secret), &timelock,
+        );
+        assert_eq!(client.get_total_locked_by_token(&token_address), 2500);
+
+        assert_eq!(
+            client.try_claim_htlc(&htlc_id, &Bytes::from_slice(&env, b"wrong guess")),
+            Err(Ok(EscrowError::InvalidPreimage)),
+        );
+        client.claim_htlc(&htlc_id, &secret);
+
+        assert_eq!(token_client.balance(&recipient), 2500);
+        let htlc = client.get_htlc(&htlc_id).unwrap();
+        assert_eq!(htlc.status, HtlcStatus::Claimed);
+        assert_eq!(htlc.preimage, Some(secret));
+
+        assert_eq!(client.try_refund_htlc(&htlc_id), Err(Ok(EscrowError::InvalidHtlcState)));
+    }
+
+    #[test]
+    fn test_htlc_refund_after_expiry() {
+        let (env, _admin, sender, _, token_address, token_client, client) = setup_with_token();
+        let recipient = Address::generate(&env);
+        let secret = Bytes::from_slice(&env, b"atomic swap secret");
+        let timelock = env.ledger().timestamp() + 3600;
+
+        let htlc_id = client.create_htlc(
+            &sender, &recipient, &token_address, &2500, &hashlock(&env, &secret), &timelock,
+        );
+
+        assert_eq!(client.try_refund_htlc(&htlc_id), Err(Ok(EscrowError::HtlcNotExpired)));
+
+        env.ledger().with_mut(|li| li.timestamp = timelock);
+        assert_eq!(client.try_claim_htlc(&htlc_id, &secret), Err(Ok(EscrowError::HtlcExpired)));
+        client.refund_htlc(&htlc_id);
+
+        assert_eq!(token_client.balance(&sender), 100000);
+        assert_eq!(client.get_htlc(&htlc_id).unwrap().status, HtlcStatus::Refunded);
+        assert_eq!(client.get_total_locked_by_token(&token_address), 0);
+    }
+
+    // ============== Dutch Auction Liquidation Tests ==============
+
+    fn setup_auction() -> (
+        Env,
+        Address,
+        Address,
+        Address,
+        Address,
+        TokenClient<'static>,
+        EscrowContractClient<'static>,
+        MockOracleClient<'static>,
+    ) {
+        let (env, admin, borrower, lender, token_address, _, client) = setup_with_token();
+        let (loan_token, loan_client, loan_admin) = create_token_contract(&env, &admin);
+        let oracle = setup_oracle(&env, &client, &token_address, &loan_token);
+
+        let bidder = Address::generate(&env);
+        loan_admin.mint(&bidder, &100000);
+
+        // Start at 120% of oracle value, floor at 80% after 1000s, 10% penalty
+        client.set_auction_config(&token_address, &12000, &8000, &1000, &1000);
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &86400);
+
+        (env, borrower, lender, token_address, bidder, loan_client, client, oracle)
+    }
+
+    fn oracle_token_with_balance(
+        env: &Env,
+        client: &EscrowContractClient,
+        oracle: &MockOracleClient,
+        holder: &Address,
+    ) -> Address {
+        let token_admin = Address::generate(env);
+        let (token, _, token_sac) = create_token_contract(env, &token_admin);
+        token_sac.mint(holder, &10000);
+
+        oracle.set_price(&token, &100);
+        client.set_collateral_haircut(&token, &0);
+        client.set_liquidation_threshold(&token, &8000);
+
+        token
+    }
+
+    #[test]
+    fn test_liquidation_starts_auction() {
+        let (env, _, lender, token_address, _, _, client, oracle) = setup_auction();
+
+        oracle.set_price(&token_address, &70);
+        client.liquidate(&1);
+
+        let escrow = client.get_escrow(&1).unwrap();
+        assert!(escrow.is_liquidated);
+        assert_eq!(TokenClient::new(&env, &token_address).balance(&lender), 0);
+        assert_eq!(client.get_total_locked_by_token(&token_address), 5000);
+
+        let auction = client.get_auction(&1).unwrap();
+        assert!(auction.is_active);
+        assert_eq!(auction.debt_remaining, 3300);
+
+        // Lot is worth 3500 in the loan token
+        assert_eq!(client.get_auction_price(&1, &token_address), 4200);
+        env.ledger().with_mut(|li| li.timestamp += 500);
+        assert_eq!(client.get_auction_price(&1, &token_address), 3500);
+        env.ledger().with_mut(|li| li.timestamp += 5000);
+        assert_eq!(client.get_auction_price(&1, &token_address), 2800);
+    }
+
+    #[test]
+    fn test_auction_bid_pays_lender_and_returns_surplus() {
+        let (env, borrower, lender, token_address, bidder, loan_client, client, oracle) = setup_auction();
+
+        oracle.set_price(&token_address, &70);
+        client.liquidate(&1);
+
+        env.ledger().with_mut(|li| li.timestamp += 500);
+        assert_eq!(client.bid(&1, &token_address, &bidder), 3500);
+
+        assert_eq!(loan_client.balance(&lender), 3300);
+        assert_eq!(loan_client.balance(&borrower), 200);
+        assert_eq!(TokenClient::new(&env, &token_address).balance(&bidder), 5000);
+        assert_eq!(client.get_total_locked_by_token(&token_address), 0);
+
+        let auction = client.get_auction(&1).unwrap();
+        assert!(!auction.is_active);
+        assert_eq!(auction.debt_remaining, 0);
+    }
+
+    #[test]
+    fn test_auction_shortfall_at_floor() {
+        let (env, borrower, lender, token_address, bidder, loan_client, client, oracle) = setup_auction();
+
+        oracle.set_price(&token_address, &70);
+        client.liquidate(&1);
+
+        env.ledger().with_mut(|li| li.timestamp += 1000);
+        assert_eq!(client.bid(&1, &token_address, &bidder), 2800);
+
+        assert_eq!(loan_client.balance(&lender), 2800);
+        assert_eq!(loan_client.balance(&borrower), 0);
+        assert_eq!(client.get_auction(&1).unwrap().debt_remaining, 500);
+    }
+
+    #[test]
+    fn test_auction_returns_unsold_lots_once_debt_covered() {
+        let (env, borrower, lender, token_address, bidder, loan_client, client, oracle) = setup_auction();
+        let usdc = oracle_token_with_balance(&env, &client, &oracle, &borrower);
+
+        client.set_auction_config(&usdc, &12000, &8000, &1000, &1000);
+        client.add_collateral_token(&1, &usdc, &500);
+        oracle.set_price(&token_address, &60);
+        client.liquidate_by(&1, &bidder);
+
+        assert_eq!(client.bid(&1, &token_address, &bidder), 3600);
+        assert_eq!(loan_client.balance(&lender), 3300);
+        assert_eq!(loan_client.balance(&borrower), 300);
+
+        assert_eq!(TokenClient::new(&env, &usdc).balance(&borrower), 10000);
+        assert_eq!(client.get_total_locked_by_token(&usdc), 0);
+        assert!(!client.get_auction(&1).unwrap().is_active);
+    }
+
+    #[test]
+    fn test_auction_penalty_without_primary_token() {
+        let (env, borrower, _, token_address, bidder, loan_client, client, oracle) = setup_auction();
+        let usdc = oracle_token_with_balance(&env, &client, &oracle, &borrower);
+
+        // The primary token is withdrawn entirely; only the basket token is left
+        client.set_auction_config(&usdc, &12000, &8000, &1000, &500);
+        client.add_collateral_token(&1, &usdc, &5000);
+        client.withdraw_collateral(&1, &5000);
+
+        env.ledger().with_mut(|li| li.timestamp += 86400 * 2 + 1);
+        oracle.set_price(&usdc, &100);
+        oracle.set_price(&loan_client.address, &100);
+        client.liquidate_by(&1, &bidder);
+
+        let auction = client.get_auction(&1).unwrap();
+        assert_eq!(auction.lots.len(), 1);
+        assert!(auction.lots.get(token_address).is_none());
+        assert_eq!(auction.debt_remaining, 3150);
+    }
+
+    #[test]
+    fn test_auction_penalty_weighted_by_lot_value() {
+        let (env, borrower, _, token_address, bidder, _, client, oracle) = setup_auction();
+        let usdc = oracle_token_with_balance(&env, &client, &oracle, &borrower);
+
+        client.set_auction_config(&usdc, &12000, &8000, &1000, &0);
+        client.add_collateral_token(&1, &usdc, &5000);
+        oracle.set_price(&token_address, &10);
+        oracle.set_price(&usdc, &10);
+        client.liquidate_by(&1, &bidder);
+
+        // Equal-value lots at 10% and 0% penalty blend to 5%
+        assert_eq!(client.get_auction(&1).unwrap().debt_remaining, 3150);
+    }
+
+    #[test]
+    fn test_close_unsold_auction() {
+        let (env, _, lender, token_address, bidder, _, client, oracle) = setup_auction();
+        client.configure_loan(&1, &1000, &InterestMode::Simple);
+
+        oracle.set_price(&token_address, &70);
+        client.liquidate(&1);
+
+        // Lots reach the floor after 1000s and then sit there a further day
+        env.ledger().with_mut(|li| li.timestamp += 1000 + 86400);
+        assert_eq!(client.try_close_auction(&1), Err(Ok(EscrowError::TimeoutNotReached)));
+
+        env.ledger().with_mut(|li| li.timestamp += 1);
+        client.close_auction(&1);
+
+        assert_eq!(TokenClient::new(&env, &token_address).balance(&lender), 5000);
+        assert_eq!(client.get_total_locked_by_token(&token_address), 0);
+        assert!(!client.get_auction(&1).unwrap().is_active);
+        assert_eq!(client.try_bid(&1, &token_address, &bidder), Err(Ok(EscrowError::AuctionClosed)));
+        assert_eq!(client.try_close_auction(&1), Err(Ok(EscrowError::AuctionClosed)));
+
+        // The loan is settled by the auction and stops accruing interest
+        env.ledger().with_mut(|li| li.timestamp += 86400 * 365);
+        assert_eq!(client.get_outstanding_debt(&1), 0);
+    }
+
+    #[test]
+    fn test_auction_settles_loan_account() {
+        let (env, _, _, token_address, bidder, _, client, oracle) = setup_auction();
+        client.configure_loan(&1, &1000, &InterestMode::Simple);
+
+        oracle.set_price(&token_address, &70);
+        client.liquidate(&1);
+        env.ledger().with_mut(|li| li.timestamp += 1000);
+        client.bid(&1, &token_address, &bidder);
+
+        let account = client.get_loan_account(&1).unwrap();
+        assert_eq!(account.principal, 0);
+        assert_eq!(account.interest, 0);
+        assert_eq!(client.get_outstanding_debt(&1), 0);
+    }
+
+    #[test]
+    fn test_liquidation_rejects_partly_configured_basket() {
+        let (env, borrower, lender, token_address, bidder, _, client, oracle) = setup_auction();
+        let usdc = oracle_token_with_balance(&env, &client, &oracle, &borrower);
+
+        client.add_collateral_token(&1, &usdc, &500);
+        oracle.set_price(&token_address, &60);
+
+        // The basket token would otherwise be handed to the lender outright
+        assert_eq!(
+            client.try_liquidate_by(&1, &bidder),
+            Err(Ok(EscrowError::AuctionNotConfigured)),
+        );
+        assert_eq!(TokenClient::new(&env, &usdc).balance(&lender), 0);
+        assert!(!client.get_escrow(&1).unwrap().is_liquidated);
+    }
+
+    #[test]
+    fn test_set_auction_config_bounds() {
+        let (_env, _admin, _, _, token_address, _, client) = setup_with_token();
+
+        // A zero floor would let the auction decay to giving collateral away
+        assert_eq!(
+            client.try_set_auction_config(&token_address, &12000, &0, &1000, &1000),
+            Err(Ok(EscrowError::InvalidConfig)),
+        );
+        assert_eq!(
+            client.try_set_auction_config(&token_address, &12000, &10001, &1000, &1000),
+            Err(Ok(EscrowError::InvalidConfig)),
+        );
+        assert_eq!(
+            client.try_set_auction_config(&token_address, &12000, &8000, &1000, &2001),
+            Err(Ok(EscrowError::InvalidConfig)),
+        );
+        client.set_auction_config(&token_address, &12000, &8000, &1000, &2000);
+    }
+
+    // ============== Index Query Tests ==============
+
+    #[test]
+    fn test_list_escrows_by_borrower_paginates() {
+        let (_env, _admin, borrower, lender, token_address, _, client) = setup_with_token();
+
+        for loan_id in 1..=5u64 {
+            client.lock_collateral(&loan_id, &borrower, &lender, &token_address, &1000, &500, &86400);
+        }
+
+        let page = client.list_escrows_by_borrower(&borrower, &None, &0, &2);
+        assert_eq!(page.escrows.len(), 2);
+        assert_eq!(page.escrows.get(0).unwrap().loan_id, 1);
+        assert_eq!(page.next_cursor, Some(2));
+
+        let page = client.list_escrows_by_borrower(&borrower, &None, &2, &2);
+        assert_eq!(page.escrows.get(0).unwrap().loan_id, 3);
+        assert_eq!(page.next_cursor, Some(4));
+
+        let page = client.list_escrows_by_borrower(&borrower, &None, &4, &2);
+        assert_eq!(page.escrows.len(), 1);
+        assert_eq!(page.next_cursor, None);
+    }
+
+    #[test]
+    fn test_list_escrows_by_status() {
+        let (env, _admin, borrower, lender, token_address, _, client) = setup_with_token();
+        let other_lender = Address::generate(&env);
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &1000, &500, &86400);
+        client.lock_collateral(&2, &borrower, &lender, &token_address, &1000, &500, &86400);
+        client.lock_collateral(&3, &borrower, &other_lender, &token_address, &1000, &500, &86400);
+        client.release_collateral(&1);
+        client.liquidate(&3);
+
+        let locked = client.list_escrows_by_borrower(&borrower, &Some(EscrowStatus::Locked), &0, &10);
+        assert_eq!(locked.escrows.len(), 1);
+        assert_eq!(locked.escrows.get(0).unwrap().loan_id, 2);
+
+        let released = client.list_escrows_by_lender(&lender, &Some(EscrowStatus::Released), &0, &10);
+        assert_eq!(released.escrows.len(), 1);
+        assert_eq!(released.escrows.get(0).unwrap().loan_id, 1);
+
+        let liquidated = client.list_escrows_by_lender(&other_lender, &Some(EscrowStatus::Liquidated), &0, &10);
+        assert_eq!(liquidated.escrows.len(), 1);
+        assert_eq!(liquidated.escrows.get(0).unwrap().loan_id, 3);
+    }
+
+    #[test]
+    fn test_list_escrows_scans_at_most_limit_entries() {
+        let (_env, _admin, borrower, lender, token_address, _, client) = setup_with_token();
+
+        for loan_id in 1..=5u64 {
+            client.lock_collateral(&loan_id, &borrower, &lender, &token_address, &1000, &500, &86400);
+        }
+        client.release_collateral(&5);
+
+        // A rare status yields empty pages that still hand back a cursor
+        let released = Some(EscrowStatus::Released);
+        let page = client.list_escrows_by_borrower(&borrower, &released, &0, &2);
+        assert_eq!(page.escrows.len(), 0);
+        assert_eq!(page.next_cursor, Some(2));
+
+        let page = client.list_escrows_by_borrower(&borrower, &released, &2, &2);
+        assert_eq!(page.escrows.len(), 0);
+        assert_eq!(page.next_cursor, Some(4));
+
+        let page = client.list_escrows_by_borrower(&borrower, &released, &4, &2);
+        assert_eq!(page.escrows.len(), 1);
+        assert_eq!(page.escrows.get(0).unwrap().loan_id, 5);
+        assert_eq!(page.next_cursor, None);
+    }
+
+    // ============== Loan Servicing Tests ==============
+
+    const YEAR: u64 = 86400 * 365;
+
+    fn setup_loan() -> (
+        Env,
+        Address,
+        Address,
+        Address,
+        TokenClient<'static>,
+        TokenClient<'static>,
+        EscrowContractClient<'static>,
+    ) {
+        let (env, admin, borrower, lender, token_address, token_client, client) = setup_with_token();
+        let (loan_token, loan_client, loan_admin) = create_token_contract(&env, &admin);
+        loan_admin.mint(&borrower, &10000);
+        client.set_loan_token(&loan_token);
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &(YEAR * 2));
+
+        (env, borrower, lender, loan_token, token_client, loan_client, client)
+    }
+
+    #[test]
+    fn test_simple_interest_accrues_on_principal() {
+        let (env, _, _, _, _, _, client) = setup_loan();
+
+        client.configure_loan(&1, &1000, &InterestMode::Simple);
+        assert_eq!(client.get_outstanding_debt(&1), 3000);
+
+        env.ledger().with_mut(|li| li.timestamp += YEAR);
+        assert_eq!(client.get_outstanding_debt(&1), 3300);
+
+        env.ledger().with_mut(|li| li.timestamp += YEAR / 2);
+        assert_eq!(client.get_outstanding_debt(&1), 3450);
+    }
+
+    #[test]
+    fn test_simple_interest_independent_of_touch_frequency() {
+        let (env, borrower, _, _, _, _, client) = setup_loan();
+
+        client.configure_loan(&1, &1000, &InterestMode::Simple);
+
+        // A tiny repayment every two days settles interest 182 times; each
+        // settlement accrues ~1.64 units, which used to truncate to 1
+        for _ in 0..182 {
+            env.ledger().with_mut(|li| li.timestamp += 2 * 86400);
+            client.repay(&1, &borrower, &1);
+        }
+        env.ledger().with_mut(|li| li.timestamp += YEAR - 182 * 2 * 86400);
+
+        let account = client.get_loan_account(&1).unwrap();
+        assert_eq!(account.principal, 3000);
+        assert_eq!(account.total_repaid + client.get_outstanding_debt(&1), 3300);
+    }
+
+    #[test]
+    fn test_compound_interest_capitalises_daily() {
+        let (env, _, _, _, _, _, client) = setup_loan();
+
+        client.configure_loan(&1, &1000, &InterestMode::Compound);
+
+        // Part of a day accrues nothing until the day completes
+        env.ledger().with_mut(|li| li.timestamp += 86399);
+        assert_eq!(client.get_outstanding_debt(&1), 3000);
+
+        env.ledger().with_mut(|li| li.timestamp += YEAR - 86399);
+        assert_eq!(client.get_outstanding_debt(&1), 3315);
+    }
+
+    #[test]
+    fn test_interest_on_large_eighteen_decimal_loans() {
+        let (env, admin, borrower, lender, _, _, client) = setup_with_token();
+        let (loan_token, _, loan_admin) = create_token_contract(&env, &admin);
+        let (collateral, _, collateral_admin) = create_token_contract(&env, &admin);
+        client.set_loan_token(&loan_token);
+
+        // A billion tokens at 18 decimals
+        let principal: i128 = 1_000_000_000 * 10i128.pow(18);
+        collateral_admin.mint(&borrower, &(principal * 4));
+        loan_admin.mint(&borrower, &(principal * 4));
+
+        client.lock_collateral(&1, &borrower, &lender, &collateral, &(principal * 2), &principal, &(YEAR * 2));
+        client.lock_collateral(&2, &borrower, &lender, &collateral, &(principal * 2), &principal, &(YEAR * 2));
+        client.configure_loan(&1, &10000, &InterestMode::Simple);
+        client.configure_loan(&2, &1000, &InterestMode::Compound);
+
+        env.ledger().with_mut(|li| li.timestamp += YEAR);
+        assert_eq!(client.get_outstanding_debt(&1), principal * 2);
+
+        let compound = client.get_outstanding_debt(&2);
+        assert!(compound > principal * 1105 / 1000 && compound < principal * 1106 / 1000);
+
+        assert_eq!(client.repay(&1, &borrower, &(principal * 2)), 0);
+        assert_eq!(client.repay(&2, &borrower, &compound), 0);
+    }
+
+    #[test]
+    fn test_repay_settles_interest_first_and_releases_collateral() {
+        let (env, borrower, lender, _, token_client, loan_client, client) = setup_loan();
+
+        client.configure_loan(&1, &1000, &InterestMode::Simple);
+        env.ledger().with_mut(|li| li.timestamp += YEAR);
+
+        assert_eq!(client.repay(&1, &borrower, &300), 3000);
+        let account = client.get_loan_account(&1).unwrap();
+        assert_eq!(account.interest, 0);
+        assert_eq!(account.principal, 3000);
+        assert!(client.get_escrow(&1).unwrap().is_locked);
+
+        // Overpayment is capped at the outstanding balance
+        assert_eq!(client.repay(&1, &borrower, &5000), 0);
+        assert_eq!(loan_client.balance(&lender), 3300);
+        assert_eq!(loan_client.balance(&borrower), 10000 - 3300);
+        assert_eq!(client.get_loan_account(&1).unwrap().total_repaid, 3300);
+
+        // Collateral comes back without the lender having to act
+        assert_eq!(token_client.balance(&borrower), 100000);
+        assert!(!client.get_escrow(&1).unwrap().is_locked);
+        assert_eq!(client.get_total_locked(), 0);
+        assert_eq!(client.try_repay(&1, &borrower, &1), Err(Ok(EscrowError::AlreadyReleased)));
+    }
+
+    #[test]
+    fn test_loan_terms_validation() {
+        let (env, borrower, lender, _, _, _, client) = setup_loan();
+        let (token_address, _, token_admin) = create_token_contract(&env, &lender);
+        token_admin.mint(&borrower, &5000);
+        client.lock_collateral(&2, &borrower, &lender, &token_address, &5000, &3000, &YEAR);
+
+        assert_eq!(
+            client.try_configure_loan(&1, &10001, &InterestMode::Simple),
+            Err(Ok(EscrowError::InvalidConfig)),
+        );
+        client.configure_loan(&1, &500, &InterestMode::Simple);
+        assert_eq!(
+            client.try_configure_loan(&1, &500, &InterestMode::Compound),
+            Err(Ok(EscrowError::LoanAlreadyConfigured)),
+        );
+
+        assert_eq!(client.try_repay(&2, &borrower, &100), Err(Ok(EscrowError::LoanNotConfigured)));
+        assert_eq!(client.try_repay(&1, &borrower, &0), Err(Ok(EscrowError::InvalidAmount)));
+    }
+
+    #[test]
+    fn test_accrued_interest_reduces_health_factor() {
+        let (env, _, _, loan_token, _, _, client) = setup_loan();
+        let token_address = client.get_escrow(&1).unwrap().collateral_token;
+        let oracle = setup_oracle(&env, &client, &token_address, &loan_token);
+
+        client.configure_loan(&1, &1000, &InterestMode::Simple);
+        assert_eq!(client.get_health_factor(&1), 13333);
+
+        env.ledger().with_mut(|li| li.timestamp += YEAR);
+        oracle.set_price(&token_address, &100);
+        oracle.set_price(&loan_token, &100);
+        assert_eq!(client.get_health_factor(&1), 12121);
+    }
+
+    // ============== Storage TTL Tests ==============
+
+    // Default minimum persistent TTL in the test ledger is 4096 ledgers.
+    const PAST_DEFAULT_TTL: u32 = 10_000;
+
+    #[test]
+    fn test_active_escrow_survives_default_ttl() {
+        let (env, admin, borrower, lender, token_address, _, client) = setup_with_token();
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &(YEAR * 2));
+
+        env.ledger().with_mut(|li| li.sequence_number += PAST_DEFAULT_TTL);
+
+        let escrow = client.get_escrow(&1).unwrap();
+        assert!(escrow.is_locked);
+        assert_eq!(client.get_admin(), admin);
+    }
+
+    #[test]
+    fn test_keeper_bumps_escrows() {
+        let (env, _admin, borrower, lender, token_address, _, client) = setup_with_token();
+        let keeper = Address::generate(&env);
+        client.set_keeper(&keeper);
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &(YEAR * 2));
+
+        // Just short of the extended TTL, then far enough past it that the
+        // escrow would have been archived without the keeper's bump.
+        env.ledger().with_mut(|li| li.sequence_number += 17_280 * 179);
+        assert_eq!(client.bump_escrows(&keeper, &vec![&env, 1u64, 99u64]), 1);
+
+        env.ledger().with_mut(|li| li.sequence_number += 17_280 * 60);
+        assert!(client.get_escrow(&1).unwrap().is_locked);
+        assert_eq!(client.get_keeper(), Some(keeper));
+
+        // Per-token settings and the party indexes survive with the escrow
+        assert_eq!(client.get_total_locked_by_token(&token_address), 5000);
+        assert_eq!(client.list_escrows_by_borrower(&borrower, &None, &0, &10).escrows.len(), 1);
+        assert_eq!(client.list_escrows_by_lender(&lender, &None, &0, &10).escrows.len(), 1);
+    }
+
+    #[test]
+    fn test_keeper_bumps_agreements() {
+        let (env, _admin, buyer, _, token_address, _, client) = setup_with_token();
+        let keeper = Address::generate(&env);
+        let seller = Address::generate(&env);
+        client.set_keeper(&keeper);
+
+        let deal_id = client.create_deal(&buyer, &seller, &None, &token_address, &4000, &(YEAR * 2));
+
+        env.ledger().with_mut(|li| li.sequence_number += 17_280 * 179);
+        assert_eq!(
+            client.bump_agreements(&keeper, &vec![&env, deal_id, 99u64], &vec![&env], &vec![&env, 1u64]),
+            1,
+        );
+
+        env.ledger().with_mut(|li| li.sequence_number += 17_280 * 60);
+        assert_eq!(client.get_deal(&deal_id).unwrap().status, DealStatus::Funded);
+    }
+
+    #[test]
+    fn test_touching_agreements_extends_ttl() {
+        let (env, _admin, buyer, _, token_address, _, client) = setup_with_token();
+        let seller = Address::generate(&env);
+        let arbiter = Some(Address::generate(&env));
+
+        let deal_id = client.create_deal(&buyer, &seller, &arbiter, &token_address, &4000, &(YEAR * 2));
+
+        // Each state change refreshes the deal, with no keeper involved
+        env.ledger().with_mut(|li| li.sequence_number += 17_280 * 179);
+        client.mark_delivered(&deal_id);
+
+        env.ledger().with_mut(|li| li.sequence_number += 17_280 * 60);
+        client.open_dispute(&deal_id);
+        assert_eq!(client.get_deal(&deal_id).unwrap().status, DealStatus::Disputed);
+    }
+
+    #[test]
+    fn test_bump_escrows_requires_admin_or_keeper() {
+        let (env, admin, _, _, _, _, client) = setup_with_token();
+        let stranger = Address::generate(&env);
+
+        assert_eq!(
+            client.try_bump_escrows(&stranger, &vec![&env, 1u64]),
+            Err(Ok(EscrowError::Unauthorized)),
+        );
+        assert_eq!(client.try_bump_escrows(&admin, &vec![&env, 1u64]), Ok(Ok(0)));
+    }
+
+    // ============== Admin Handover & Multisig Tests ==============
+
+    #[test]
+    fn test_admin_handover_expires() {
+        let (env, admin, _, _, client) = setup_test_env();
+        let new_admin = Address::generate(&env);
+
+        assert_eq!(client.try_accept_admin(), Err(Ok(EscrowError::NoPendingAdmin)));
+
+        client.propose_admin(&new_admin, &3600);
+        env.ledger().with_mut(|li| li.timestamp += 3601);
+
+        assert_eq!(client.try_accept_admin(), Err(Ok(EscrowError::HandoverExpired)));
+        assert_eq!(client.get_admin(), admin);
+    }
+
+    #[test]
+    fn test_multisig_liquidation_needs_threshold() {
+        let (env, _admin, borrower, lender, token_address, token_client, client) = setup_with_token();
+        let signers = vec![
+            &env,
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ];
+        client.set_signer_set(&signers, &2);
+
+        client.lock_collateral(&1, &borrower, &lender, &token_address, &5000, &3000, &86400);
+
+        let action = AdminAction::Liquidate(1);
+        assert_eq!(client.try_liquidate(&1), Err(Ok(EscrowError::InsufficientApprovals)));
+
+        assert_eq!(client.approve_action(&signers.get(0).unwrap(), &action), 1);
+        assert_eq!(
+            client.try_approve_action(&signers.get(0).unwrap(), &action),
+            Err(Ok(EscrowError::AlreadyApproved)),
+        );
+        assert_eq!(
+            client.try_approve_action(&Address::generate(&env), &action),
+            Err(Ok(EscrowError::NotSigner)),
+        );
+        assert_eq!(client.try_liquidate(&1), Err(Ok(EscrowError::InsufficientApprovals)));
+
+        assert_eq!(client.approve_action(&signers.get(2).unwrap(), &action), 2);
+        client.liquidate(&1);
+
+        assert_eq!(token_client.balance(&lender), 5000);
+        assert!(client.get_approvals(&action).is_empty());
+    }
+
+    #[test]
+    fn test_multisig_admin_changes() {
+        let (env, admin, _, _, client) = setup_test_env();
+        let signers = vec![&env, Address::generate(&env), Address::generate(&env)];
+
+        assert_eq!(client.try_set_signer_set(&signers, &3), Err(Ok(EscrowError::InvalidConfig)));
+        client.set_signer_set(&signers, &2);
+        assert_eq!(client.get_signer_set().unwrap().threshold, 2);
+
+        let new_admin = Address::generate(&env);
+        assert_eq!(
+            client.try_propose_admin(&new_admin, &3600),
+            Err(Ok(EscrowError::InsufficientApprovals)),
+        );
+
+        // Approvals are bound to the exact action, expiry included
+        let action = AdminAction::ProposeAdmin(new_admin.clone(), 3600);
+        for signer in signers.iter() {
+            client.approve_action(&signer, &action);
+        }
+        assert_eq!(
+            client.try_propose_admin(&new_admin, &7200),
+            Err(Ok(EscrowError::InsufficientApprovals)),
+        );
+        client.propose_admin(&new_admin, &3600);
+        client.accept_admin();
+        assert_eq!(client.get_admin(), new_admin);
+        assert_ne!(client.get_admin(), admin);
+
+        // Dropping the signer set also needs the signers
+        let empty = soroban_sdk::Vec::<Address>::new(&env);
+        let removal = AdminAction::SetSignerSet(empty.clone(), 0);
+        for signer in signers.iter() {
+            client.approve_action(&signer, &removal);
+        }
+        client.set_signer_set(&empty, &0);
+        assert!(client.get_signer_set().is_none());
+    }
+
+    #[test]
+    fn test_multisig_approvals_expire() {
+        let (env, _admin, _, _, client) = setup_test_env();
+        let signers = vec![&env, Address::generate(&env), Address::generate(&env)];
+        client.set_signer_set(&signers, &2);
+
+        let keeper = Address::generate(&env);
+        let action = AdminAction::SetKeeper(keeper.clone());
+        client.approve_action(&signers.get(0).unwrap(), &action);
+
+        // A stale approval cannot be combined with a fresh one
+        env.ledger().with_mut(|li| li.timestamp += 7 * 86400 + 1);
+        assert!(client.get_approvals(&action).is_empty());
+        client.approve_action(&signers.get(1).unwrap(), &action);
+        assert_eq!(client.try_set_keeper(&keeper), Err(Ok(EscrowError::InsufficientApprovals)));
+
+        // The first signer may renew theirs
+        assert_eq!(client.approve_action(&signers.get(0).unwrap(), &action), 2);
+        client.set_keeper(&keeper);
+        assert_eq!(client.get_keeper(), Some(keeper));
+    }
+
+    #[test]
+    fn test_propose_admin_rejects_overflowing_expiry() {
+        let (env, _admin, _, _, client) = setup_test_env();
+        env.ledger().with_mut(|li| li.timestamp = 1000);
+
+        assert_eq!(
+            client.try_propose_admin(&Address::generate(&env), &u64::MAX),
+            Err(Ok(EscrowError::InvalidConfig)),
+        );
+        assert!(client.get_pending_admin().is_none());
+    }
+
+    #[test]
+    fn test_multisig_guards_admin_setters() {
+        let (env, _admin, _, _, token_address, _, client) = setup_with_token();
+        let signers = vec![&env, Address::generate(&env), Address::generate(&env)];
+        client.set_signer_set(&signers, &2);
+
+        // The admin key alone can no longer change any risk parameter
+        let rogue = Address::generate(&env);
+        assert_eq!(client.try_set_keeper(&rogue), Err(Ok(EscrowError::InsufficientApprovals)));
+        assert_eq!(
+            client.try_set_min_collateral_ratio(&10000),
+            Err(Ok(EscrowError::InsufficientApprovals)),
+        );
+        assert_eq!(client.try_set_oracle(&rogue, &300), Err(Ok(EscrowError::InsufficientApprovals)));
+        assert_eq!(client.try_set_loan_token(&rogue), Err(Ok(EscrowError::InsufficientApprovals)));
+        assert_eq!(
+            client.try_set_liquidation_threshold(&token_address, &9000),
+            Err(Ok(EscrowError::InsufficientApprovals)),
+        );
+        assert_eq!(
+            client.try_set_liquidation_config(&0, &5000),
+            Err(Ok(EscrowError::InsufficientApprovals)),
+        );
+        assert_eq!(
+            client.try_set_collateral_haircut(&token_address, &0),
+            Err(Ok(EscrowError::InsufficientApprovals)),
+        );
+        assert_eq!(
+            client.try_set_auction_config(&token_address, &12000, &8000, &1000, &1000),
+            Err(Ok(EscrowError::InsufficientApprovals)),
+        );
+        assert!(client.get_oracle().is_none());
+
+        // With the signers' approvals the same call goes through once
+        let action = AdminAction::SetOracle(rogue.clone(), 300);
+        for signer in signers.iter() {
+            client.approve_action(&signer, &action);
+        }
+        client.set_oracle(&rogue, &300);
+        assert_eq!(client.get_oracle().unwrap().oracle, rogue);
+        assert_eq!(client.try_set_oracle(&rogue, &300), Err(Ok(EscrowError::InsufficientApprovals)));
+    }
 }
@@ -10,7 +10,7 @@
 
 use soroban_sdk::{
     contract, contractimpl, contracttype, Env, Address, String, Symbol, token,
-    Vec, Map,
+    Vec, Map, BytesN,
 };
 
 // ============== Data Structures ==============
@@ -36,6 +36,14 @@ pub struct RWAAsset {
     pub accredited_only: bool,        // Only accredited investors can hold
 }
 
+/// Who may hold an asset, fixed at creation
+#[contracttype]
+#[derive(Clone)]
+pub struct ComplianceTerms {
+    pub min_investment: i128,         // Minimum tokens per investment
+    pub accredited_only: bool,        // Only accredited investors can hold
+}
+
 /// Type of real-world asset
 #[contracttype]
 #[derive(Clone, PartialEq)]
@@ -77,6 +85,40 @@ pub struct Holding {
     pub locked_until: u64,            // Lock-up period
 }
 
+/// Per-asset country restrictions and holder limits
+#[contracttype]
+#[derive(Clone)]
+pub struct JurisdictionRules {
+    pub allowed_countries: Vec<String>, // Empty allows any whitelisted country
+    pub blocked_countries: Vec<String>,
+    pub max_holders: u32,               // 0 means no limit
+}
+
+/// Result of an eligibility check, naming the first rule that failed
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Eligibility {
+    Eligible,
+    Blacklisted,
+    AssetNotFound,
+    AssetInactive,
+    NotRegistered,
+    KycExpired,
+    NotAccredited,
+    CountryNotAllowed,
+    CountryBlocked,
+    MaxHoldersReached,
+    CountryCapReached,
+}
+
+/// Tokens from one purchase that stay locked until `unlocks_at`
+#[contracttype]
+#[derive(Clone)]
+pub struct LockupTranche {
+    pub amount: i128,
+    pub unlocks_at: u64,
+}
+
 /// Dividend/Yield distribution record
 #[contracttype]
 #[derive(Clone)]
@@ -86,9 +128,126 @@ pub struct Distribution {
     pub total_amount: i128,
     pub per_token_amount: i128,
     pub distribution_token: Address,  // Token used for distribution
-    pub snapshot_time: u64,
+    pub reward_index: i128,           // Cumulative reward index after this distribution
+    pub index_delta: i128,            // Reward per token this distribution added
+    pub eligible_supply: i128,        // Circulating supply at the snapshot
+    pub snapshot_time: u64,           // Balances held at this time earn it; always `created_at`
     pub created_at: u64,
-    pub is_claimed: bool,
+    pub claim_deadline: u64,          // Unclaimed funds can be reclaimed after this
+    pub claimed: i128,                // Amount paid to holders, scaled by REWARD_SCALE
+    pub claimant_count: u32,          // Holders who claimed a share of it
+    pub is_reclaimed: bool,
+}
+
+/// Claim progress of a distribution
+#[contracttype]
+#[derive(Clone)]
+pub struct DistributionStatus {
+    pub distribution_id: u64,
+    pub total_amount: i128,
+    pub claimed_amount: i128,
+    pub claimant_count: u32,
+    pub remaining: i128,
+    pub claim_deadline: u64,
+    pub is_reclaimed: bool,
+}
+
+/// Balance of a holder from `timestamp` until the next checkpoint
+#[contracttype]
+#[derive(Clone)]
+pub struct BalanceCheckpoint {
+    pub timestamp: u64,
+    pub balance: i128,
+}
+
+/// An investor's position in one reward stream (asset, distribution token)
+#[contracttype]
+#[derive(Clone)]
+pub struct RewardState {
+    pub index: i128,                  // Reward index the accrual is settled up to
+    pub accrued: i128,                // Unpaid rewards, scaled by REWARD_SCALE
+    pub claimed_index: i128,          // Reward index at the last payout
+}
+
+/// Issuer-run redemption window for an asset
+#[contracttype]
+#[derive(Clone)]
+pub struct RedemptionConfig {
+    pub payment_token: Address,       // Token redemptions are paid in (USD cents)
+    pub fee_bps: u32,                 // Redemption fee kept by the issuer
+    pub is_open: bool,
+}
+
+/// Status of a redemption request
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum RedemptionStatus {
+    Pending,
+    Settled,
+    Cancelled,
+}
+
+/// Investor request to redeem tokens at NAV
+#[contracttype]
+#[derive(Clone)]
+pub struct RedemptionRequest {
+    pub request_id: u64,
+    pub asset_id: u64,
+    pub investor: Address,
+    pub amount: i128,
+    pub requested_at: u64,
+    pub payout: i128,                 // Net amount paid, set on settlement
+    pub status: RedemptionStatus,
+}
+
+/// Rules for accepting appraisals of an asset
+#[contracttype]
+#[derive(Clone)]
+pub struct ValuationConfig {
+    pub quorum: u32,                  // Agreeing appraisals needed to accept a value
+    pub tolerance_bps: u32,           // Allowed spread of an appraisal around the median
+    pub max_deviation_bps: u32,       // Max change from the current value (0 = no limit)
+    pub staleness_window: u64,        // Seconds before invest is blocked (0 = never stale)
+}
+
+/// An appraiser's pending valuation of an asset
+#[contracttype]
+#[derive(Clone)]
+pub struct ValuationSubmission {
+    pub appraiser: Address,
+    pub value_usd: i128,
+    pub submitted_at: u64,
+}
+
+/// An accepted valuation
+#[contracttype]
+#[derive(Clone)]
+pub struct ValuationRecord {
+    pub timestamp: u64,
+    pub value_usd: i128,
+    pub attestations: u32,            // Appraisals the value was the median of
+}
+
+/// Legal document attached to an asset (offering memorandum, deed, audit...)
+#[contracttype]
+#[derive(Clone)]
+pub struct Document {
+    pub name: String,
+    pub uri: String,
+    pub content_hash: BytesN<32>,     // SHA-256 of the document contents
+    pub last_modified: u64,
+    pub requires_acknowledgement: bool, // Investors must acknowledge it before investing
+}
+
+/// Platform roles granted on-chain
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Role {
+    PlatformAdmin,
+    Issuer,
+    ComplianceOfficer,
+    ValuationAgent,
+    Custodian,
 }
 
 /// Storage keys
@@ -101,11 +260,555 @@ pub enum RWAKey {
     Investor(Address),                // address -> Investor
     Holding(u64, Address),            // (asset_id, investor) -> Holding
     Distribution(u64),                // distribution_id -> Distribution
-    ClaimedDistribution(u64, Address), // (distribution_id, investor) -> bool
     WhitelistedCountry(String),       // country_code -> bool
     BlacklistedAddress(Address),      // address -> bool
     AssetToken(u64),                  // asset_id -> token Address
     TotalValueLocked,                 // Total USD value locked
+    CheckpointCount(u64, Address),    // (asset_id, investor) -> number of checkpoints
+    Checkpoint(u64, Address, u32),    // (asset_id, investor, index) -> BalanceCheckpoint
+    RewardTokens(u64),                // asset_id -> Vec<Address> of distribution tokens
+    RewardIndex(u64, Address),        // (asset_id, token) -> cumulative reward per token
+    RewardDust(u64, Address),         // (asset_id, token) -> undistributed scaled remainder
+    Reward(u64, Address, Address),    // (asset_id, token, investor) -> RewardState
+    RewardDistributions(u64, Address), // (asset_id, token) -> Vec<u64> of distribution ids
+    RewardCredit(u64, Address),       // (distribution_id, investor) -> scaled share credited, not yet paid
+    ClaimPeriod,                      // Seconds holders have to claim a distribution
+    RedemptionConfig(u64),            // asset_id -> RedemptionConfig
+    RedemptionLiquidity(u64),         // asset_id -> payment tokens funded by the issuer
+    RedemptionQueue(u64),             // asset_id -> Vec<u64> of pending request ids
+    Redemption(u64),                  // request_id -> RedemptionRequest
+    RedemptionCounter,
+    LockupPeriod(u64),                // asset_id -> lock-up seconds applied on invest
+    Lockups(u64, Address),            // (asset_id, investor) -> Vec<LockupTranche>
+    JurisdictionRules(u64),           // asset_id -> JurisdictionRules
+    CountryHolderCap(u64, String),    // (asset_id, country_code) -> max holders
+    HolderCount(u64),                 // asset_id -> number of non-zero holdings
+    CountryHolderCount(u64, String),  // (asset_id, country_code) -> number of holders
+    HeldAssetCount(Address),          // investor -> number of assets they hold
+    RoleMember(Role, Address),        // (role, account) -> bool
+    ValuationConfig(u64),             // asset_id -> ValuationConfig
+    ValuationRound(u64),              // asset_id -> Vec<ValuationSubmission> awaiting quorum
+    ValuationHistory(u64),            // asset_id -> Vec<ValuationRecord>
+    Document(u64, String),            // (asset_id, name) -> Document
+    DocumentNames(u64),               // asset_id -> Vec<String>
+    Acknowledgement(u64, String, Address), // (asset_id, name, investor) -> acknowledged hash
+    FrozenAmount(u64, Address),       // (asset_id, investor) -> frozen token amount
+}
+
+/// Fixed-point scale of the reward-per-token index
+const REWARD_SCALE: i128 = 1_000_000_000_000;
+
+/// Default claim window for new distributions (one year)
+const DEFAULT_CLAIM_PERIOD: u64 = 365 * 86400;
+
+const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Valuation rules for assets without their own config. Appraisals are
+/// refused until one is set; only the (disabled) staleness check applies
+const DEFAULT_VALUATION_CONFIG: ValuationConfig = ValuationConfig {
+    quorum: 1,
+    tolerance_bps: 0,
+    max_deviation_bps: 0,
+    staleness_window: 0,
+};
+
+// ============== Internal Helpers ==============
+
+fn read_checkpoint(env: &Env, asset_id: u64, investor: &Address, index: u32) -> BalanceCheckpoint {
+    env.storage().persistent()
+        .get(&RWAKey::Checkpoint(asset_id, investor.clone(), index))
+        .unwrap()
+}
+
+/// Record a holder's balance as of the current ledger timestamp.
+/// Several changes within one ledger collapse into a single checkpoint.
+/// Each checkpoint is its own entry so a write never rewrites the history.
+/// Distributions are paid from the reward index, so nothing on-chain reads
+/// these back: every balance change pays a persistent write purely to serve
+/// `balance_of_at` to off-chain consumers (voting, tax and audit snapshots).
+fn write_checkpoint(env: &Env, asset_id: u64, investor: &Address, balance: i128) {
+    let count_key = RWAKey::CheckpointCount(asset_id, investor.clone());
+    let count: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+    
+    let now = env.ledger().timestamp();
+    let checkpoint = BalanceCheckpoint { timestamp: now, balance };
+    if count > 0 && read_checkpoint(env, asset_id, investor, count - 1).timestamp == now {
+        env.storage().persistent()
+            .set(&RWAKey::Checkpoint(asset_id, investor.clone(), count - 1), &checkpoint);
+    } else {
+        env.storage().persistent()
+            .set(&RWAKey::Checkpoint(asset_id, investor.clone(), count), &checkpoint);
+        env.storage().persistent().set(&count_key, &(count + 1));
+    }
+}
+
+/// Bring an investor's accrued rewards in one stream up to the current index.
+/// Must run with the balance held since the last settlement, i.e. before
+/// any change to it.
+fn settle_reward(env: &Env, asset_id: u64, token: &Address, investor: &Address, balance: i128) -> RewardState {
+    let index: i128 = env.storage().persistent()
+        .get(&RWAKey::RewardIndex(asset_id, token.clone()))
+        .unwrap_or(0);
+    let key = RWAKey::Reward(asset_id, token.clone(), investor.clone());
+    let mut state: RewardState = env.storage().persistent()
+        .get(&key)
+        .unwrap_or(RewardState { index: 0, accrued: 0, claimed_index: 0 });
+    
+    state.accrued += accrue_distributions(env, asset_id, token, investor, state.index, balance, true);
+    state.index = index;
+    env.storage().persistent().set(&key, &state);
+    
+    state
+}
+
+/// Scaled rewards a balance earned from the distributions made since
+/// `since_index`. Distributions past their claim deadline pay nothing. With
+/// `record` set, each share is credited to the holder against its
+/// distribution; it only counts as claimed once paid out.
+fn accrue_distributions(
+    env: &Env,
+    asset_id: u64,
+    token: &Address,
+    investor: &Address,
+    since_index: i128,
+    balance: i128,
+    record: bool,
+) -> i128 {
+    if balance <= 0 {
+        return 0;
+    }
+    
+    let ids: Vec<u64> = env.storage().persistent()
+        .get(&RWAKey::RewardDistributions(asset_id, token.clone()))
+        .unwrap_or(Vec::new(env));
+    let now = env.ledger().timestamp();
+    
+    // Newest first, stopping at the first distribution already settled
+    let mut total = 0;
+    for id in ids.iter().rev() {
+        let distribution: Distribution = env.storage().persistent()
+            .get(&RWAKey::Distribution(id))
+            .expect("Distribution not found");
+        if distribution.reward_index <= since_index {
+            break;
+        }
+        if now > distribution.claim_deadline {
+            continue;
+        }
+        
+        let share = balance * distribution.index_delta;
+        total += share;
+        if record {
+            let credit_key = RWAKey::RewardCredit(id, investor.clone());
+            let credit: i128 = env.storage().persistent().get(&credit_key).unwrap_or(0);
+            env.storage().persistent().set(&credit_key, &(credit + share));
+        }
+    }
+    
+    total
+}
+
+/// Walk the credits an investor has received since the last payout and
+/// return the scaled amount that has expired. With `record` set, the
+/// credits are consumed: live ones are added to their distribution's
+/// claimed total and claimant count, expired ones are dropped and stay
+/// with the distribution for the issuer to reclaim.
+fn collect_credits(
+    env: &Env,
+    asset_id: u64,
+    token: &Address,
+    investor: &Address,
+    since_index: i128,
+    record: bool,
+) -> i128 {
+    let ids: Vec<u64> = env.storage().persistent()
+        .get(&RWAKey::RewardDistributions(asset_id, token.clone()))
+        .unwrap_or(Vec::new(env));
+    let now = env.ledger().timestamp();
+    
+    let mut expired = 0;
+    for id in ids.iter().rev() {
+        let mut distribution: Distribution = env.storage().persistent()
+            .get(&RWAKey::Distribution(id))
+            .expect("Distribution not found");
+        if distribution.reward_index <= since_index {
+            break;
+        }
+        
+        let credit_key = RWAKey::RewardCredit(id, investor.clone());
+        let credit: i128 = match env.storage().persistent().get(&credit_key) {
+            Some(c) => c,
+            None => continue,
+        };
+        let is_expired = now > distribution.claim_deadline;
+        if is_expired {
+            expired += credit;
+        }
+        if !record {
+            continue;
+        }
+        
+        env.storage().persistent().remove(&credit_key);
+        if !is_expired {
+            distribution.claimed += credit;
+            distribution.claimant_count += 1;
+            env.storage().persistent().set(&RWAKey::Distribution(id), &distribution);
+        }
+    }
+    
+    expired
+}
+
+/// Move the unpaid credits of one reward stream to a recovered wallet
+fn move_credits(env: &Env, asset_id: u64, token: &Address, from: &Address, to: &Address, since_index: i128) {
+    let ids: Vec<u64> = env.storage().persistent()
+        .get(&RWAKey::RewardDistributions(asset_id, token.clone()))
+        .unwrap_or(Vec::new(env));
+    
+    for id in ids.iter().rev() {
+        let distribution: Distribution = env.storage().persistent()
+            .get(&RWAKey::Distribution(id))
+            .expect("Distribution not found");
+        if distribution.reward_index <= since_index {
+            break;
+        }
+        
+        let from_key = RWAKey::RewardCredit(id, from.clone());
+        if let Some(credit) = env.storage().persistent().get::<_, i128>(&from_key) {
+            env.storage().persistent().set(&RWAKey::RewardCredit(id, to.clone()), &credit);
+            env.storage().persistent().remove(&from_key);
+        }
+    }
+}
+
+/// Settle every reward stream of an asset ahead of a balance change
+fn settle_rewards(env: &Env, asset_id: u64, investor: &Address, balance: i128) {
+    let tokens: Vec<Address> = env.storage().persistent()
+        .get(&RWAKey::RewardTokens(asset_id))
+        .unwrap_or(Vec::new(env));
+    for token in tokens.iter() {
+        settle_reward(env, asset_id, &token, investor, balance);
+    }
+}
+
+/// Pay out the whole units an investor has accrued in one stream. Shares
+/// of distributions past their claim deadline are forfeited, and the
+/// sub-unit remainder stays accrued for the next payout.
+fn pay_reward(env: &Env, asset_id: u64, token: &Address, investor: &Address) -> i128 {
+    let balance = env.storage().persistent()
+        .get::<_, Holding>(&RWAKey::Holding(asset_id, investor.clone()))
+        .map(|h| h.amount)
+        .unwrap_or(0);
+    let mut state = settle_reward(env, asset_id, token, investor, balance);
+    state.accrued -= collect_credits(env, asset_id, token, investor, state.claimed_index, true);
+    
+    let amount = state.accrued / REWARD_SCALE;
+    state.accrued -= amount * REWARD_SCALE;
+    state.claimed_index = state.index;
+    env.storage().persistent().set(&RWAKey::Reward(asset_id, token.clone(), investor.clone()), &state);
+    
+    if amount > 0 {
+        let client = token::Client::new(env, token);
+        client.transfer(&env.current_contract_address(), investor, &amount);
+    }
+    
+    amount
+}
+
+/// Whole units of a distribution not yet paid to any holder. Holders'
+/// balances at the snapshot sum to `eligible_supply`, so this never
+/// undercuts what has been paid.
+fn distribution_remaining(distribution: &Distribution) -> i128 {
+    (distribution.index_delta * distribution.eligible_supply - distribution.claimed) / REWARD_SCALE
+}
+
+/// Tokens of a holder still under lock-up
+fn locked_balance(env: &Env, asset_id: u64, investor: &Address) -> i128 {
+    let tranches: Vec<LockupTranche> = env.storage().persistent()
+        .get(&RWAKey::Lockups(asset_id, investor.clone()))
+        .unwrap_or(Vec::new(env));
+    let now = env.ledger().timestamp();
+    
+    let mut locked = 0;
+    for tranche in tranches.iter() {
+        if tranche.unlocks_at > now {
+            locked += tranche.amount;
+        }
+    }
+    
+    locked
+}
+
+/// Check an investor against an asset's country rules and holder caps.
+/// Caps only apply to investors who do not already hold the asset.
+fn jurisdiction_rule(env: &Env, asset_id: u64, investor: &Investor) -> Eligibility {
+    let country = investor.country_code.clone();
+    let is_holder = env.storage().persistent()
+        .get::<_, Holding>(&RWAKey::Holding(asset_id, investor.address.clone()))
+        .map(|h| h.amount > 0)
+        .unwrap_or(false);
+    
+    if let Some(rules) = env.storage().persistent()
+        .get::<_, JurisdictionRules>(&RWAKey::JurisdictionRules(asset_id))
+    {
+        if rules.blocked_countries.contains(&country) {
+            return Eligibility::CountryBlocked;
+        }
+        if !rules.allowed_countries.is_empty() && !rules.allowed_countries.contains(&country) {
+            return Eligibility::CountryNotAllowed;
+        }
+        
+        if !is_holder && rules.max_holders > 0 {
+            let holders: u32 = env.storage().persistent()
+                .get(&RWAKey::HolderCount(asset_id))
+                .unwrap_or(0);
+            if holders >= rules.max_holders {
+                return Eligibility::MaxHoldersReached;
+            }
+        }
+    }
+    
+    if !is_holder {
+        if let Some(cap) = env.storage().persistent()
+            .get::<_, u32>(&RWAKey::CountryHolderCap(asset_id, country.clone()))
+        {
+            let holders: u32 = env.storage().persistent()
+                .get(&RWAKey::CountryHolderCount(asset_id, country))
+                .unwrap_or(0);
+            if holders >= cap {
+                return Eligibility::CountryCapReached;
+            }
+        }
+    }
+    
+    Eligibility::Eligible
+}
+
+/// Panic unless an investor passes the asset's jurisdiction rules
+fn require_jurisdiction(env: &Env, asset_id: u64, investor: &Investor) {
+    match jurisdiction_rule(env, asset_id, investor) {
+        Eligibility::Eligible => {},
+        Eligibility::CountryBlocked => panic!("Country blocked for asset"),
+        Eligibility::CountryNotAllowed => panic!("Country not allowed for asset"),
+        Eligibility::MaxHoldersReached => panic!("Maximum holders reached"),
+        Eligibility::CountryCapReached => panic!("Country holder cap reached"),
+        _ => panic!("Investor not eligible"),
+    }
+}
+
+/// Keep holder counts in step when a balance moves to or from zero
+fn track_holder(env: &Env, asset_id: u64, investor: &Address, before: i128, after: i128) {
+    let delta: i32 = if before <= 0 && after > 0 {
+        1
+    } else if before > 0 && after <= 0 {
+        -1
+    } else {
+        return;
+    };
+    
+    let holders: u32 = env.storage().persistent()
+        .get(&RWAKey::HolderCount(asset_id))
+        .unwrap_or(0);
+    env.storage().persistent().set(&RWAKey::HolderCount(asset_id), &holders.saturating_add_signed(delta));
+    
+    let held_key = RWAKey::HeldAssetCount(investor.clone());
+    let held: u32 = env.storage().persistent().get(&held_key).unwrap_or(0);
+    env.storage().persistent().set(&held_key, &held.saturating_add_signed(delta));
+    
+    if let Some(record) = env.storage().persistent()
+        .get::<_, Investor>(&RWAKey::Investor(investor.clone()))
+    {
+        let key = RWAKey::CountryHolderCount(asset_id, record.country_code);
+        let holders: u32 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &holders.saturating_add_signed(delta));
+    }
+}
+
+/// Move asset tokens without the holder's signature by clawing them back
+/// and minting them to the recipient. This contract must be the asset
+/// token's admin, and the token must have clawback enabled.
+fn reassign_tokens(env: &Env, asset_id: u64, from: &Address, to: &Address, amount: i128) {
+    let asset_token: Address = env.storage().persistent()
+        .get(&RWAKey::AssetToken(asset_id))
+        .expect("Asset token not found");
+    let admin_client = token::StellarAssetClient::new(env, &asset_token);
+    admin_client.clawback(from, &amount);
+    admin_client.mint(to, &amount);
+}
+
+/// Carry lock-ups along with a forced move of `amount` tokens. Unlocked
+/// tokens are taken first; any locked tokens that go keep their unlock time.
+fn move_lockups(env: &Env, asset_id: u64, from: &Address, to: &Address, balance: i128, amount: i128) {
+    let locked = locked_balance(env, asset_id, from);
+    let mut to_move = amount - (balance - locked);
+    if to_move <= 0 {
+        return;
+    }
+    
+    let now = env.ledger().timestamp();
+    let from_key = RWAKey::Lockups(asset_id, from.clone());
+    let to_key = RWAKey::Lockups(asset_id, to.clone());
+    let from_tranches: Vec<LockupTranche> = env.storage().persistent()
+        .get(&from_key)
+        .unwrap_or(Vec::new(env));
+    let mut to_tranches: Vec<LockupTranche> = env.storage().persistent()
+        .get(&to_key)
+        .unwrap_or(Vec::new(env));
+    
+    // Latest unlocks go first so the sender keeps the tranches closest to release
+    let mut kept = Vec::new(env);
+    for tranche in from_tranches.iter().rev() {
+        if tranche.unlocks_at <= now {
+            continue;
+        }
+        let moved = tranche.amount.min(to_move);
+        to_move -= moved;
+        if moved > 0 {
+            to_tranches.push_back(LockupTranche { amount: moved, unlocks_at: tranche.unlocks_at });
+        }
+        if tranche.amount > moved {
+            kept.push_front(LockupTranche { amount: tranche.amount - moved, unlocks_at: tranche.unlocks_at });
+        }
+    }
+    
+    env.storage().persistent().set(&from_key, &kept);
+    env.storage().persistent().set(&to_key, &to_tranches);
+}
+
+fn valuation_config(env: &Env, asset_id: u64) -> ValuationConfig {
+    env.storage().persistent()
+        .get(&RWAKey::ValuationConfig(asset_id))
+        .unwrap_or(DEFAULT_VALUATION_CONFIG)
+}
+
+/// Median of a non-empty list; the mean of the middle pair for even lengths
+fn median(env: &Env, values: &Vec<i128>) -> i128 {
+    // Insertion sort: rounds are as small as the appraiser set
+    let mut sorted: Vec<i128> = Vec::new(env);
+    for value in values.iter() {
+        let mut position = sorted.len();
+        while position > 0 && sorted.get_unchecked(position - 1) > value {
+            position -= 1;
+        }
+        sorted.insert(position, value);
+    }
+    
+    let middle = sorted.len() / 2;
+    if sorted.len() % 2 == 1 {
+        sorted.get_unchecked(middle)
+    } else {
+        (sorted.get_unchecked(middle - 1) + sorted.get_unchecked(middle)) / 2
+    }
+}
+
+/// Record an accepted valuation in the asset's history
+fn push_valuation(env: &Env, asset_id: u64, value_usd: i128, attestations: u32) {
+    let key = RWAKey::ValuationHistory(asset_id);
+    let mut history: Vec<ValuationRecord> = env.storage().persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    
+    let now = env.ledger().timestamp();
+    let record = ValuationRecord { timestamp: now, value_usd, attestations };
+    match history.last() {
+        Some(last) if last.timestamp == now => history.set(history.len() - 1, record),
+        _ => history.push_back(record),
+    }
+    
+    env.storage().persistent().set(&key, &history);
+}
+
+/// Panic unless the investor acknowledged the current version of every
+/// document the asset requires
+fn require_acknowledgements(env: &Env, asset_id: u64, investor: &Address) {
+    let names: Vec<String> = env.storage().persistent()
+        .get(&RWAKey::DocumentNames(asset_id))
+        .unwrap_or(Vec::new(env));
+    
+    for name in names.iter() {
+        let document: Document = env.storage().persistent()
+            .get(&RWAKey::Document(asset_id, name.clone()))
+            .expect("Document not found");
+        if !document.requires_acknowledgement {
+            continue;
+        }
+        
+        let acknowledged: Option<BytesN<32>> = env.storage().persistent()
+            .get(&RWAKey::Acknowledgement(asset_id, name, investor.clone()));
+        if acknowledged != Some(document.content_hash) {
+            panic!("Document acknowledgement required");
+        }
+    }
+}
+
+/// Whether `account` holds `role`. The primary admin is always a platform admin.
+fn holds_role(env: &Env, role: Role, account: &Address) -> bool {
+    if role == Role::PlatformAdmin {
+        let admin: Option<Address> = env.storage().persistent().get(&RWAKey::Admin);
+        if admin.as_ref() == Some(account) {
+            return true;
+        }
+    }
+    
+    env.storage().persistent()
+        .get(&RWAKey::RoleMember(role, account.clone()))
+        .unwrap_or(false)
+}
+
+/// Panic unless `caller` signed and holds `role`
+fn require_role(env: &Env, caller: &Address, role: Role) {
+    caller.require_auth();
+    if !holds_role(env, role, caller) {
+        panic!("Missing required role");
+    }
+}
+
+/// Panic unless the asset's own issuer signed and still holds the issuer role
+fn require_issuer(env: &Env, asset: &RWAAsset) {
+    asset.issuer.require_auth();
+    if !holds_role(env, Role::Issuer, &asset.issuer) {
+        panic!("Missing required role");
+    }
+}
+
+/// Tokens of a holder frozen by a compliance agent
+fn frozen_balance(env: &Env, asset_id: u64, investor: &Address) -> i128 {
+    env.storage().persistent()
+        .get(&RWAKey::FrozenAmount(asset_id, investor.clone()))
+        .unwrap_or(0)
+}
+
+/// Part of a `balance` the holder may move. Lock-ups and freezes both
+/// restrict the same holding and frozen tokens may be locked ones, so only
+/// the larger of the two is withheld.
+fn spendable_balance(env: &Env, asset_id: u64, investor: &Address, balance: i128) -> i128 {
+    let restricted = locked_balance(env, asset_id, investor).max(frozen_balance(env, asset_id, investor));
+    (balance - restricted).max(0)
+}
+
+/// Balance held at `timestamp`: the latest checkpoint at or before it
+fn balance_at(env: &Env, asset_id: u64, investor: &Address, timestamp: u64) -> i128 {
+    let count: u32 = env.storage().persistent()
+        .get(&RWAKey::CheckpointCount(asset_id, investor.clone()))
+        .unwrap_or(0);
+    
+    // Binary search for the first checkpoint after `timestamp`
+    let mut low = 0;
+    let mut high = count;
+    while low < high {
+        let mid = (low + high) / 2;
+        if read_checkpoint(env, asset_id, investor, mid).timestamp <= timestamp {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    
+    if low == 0 {
+        0
+    } else {
+        read_checkpoint(env, asset_id, investor, low - 1).balance
+    }
 }
 
 // ============== Contract Implementation ==============
@@ -130,6 +833,13 @@ impl RWAContract {
         env.storage().persistent().set(&RWAKey::DistributionCounter, &0u64);
         env.storage().persistent().set(&RWAKey::TotalValueLocked, &0i128);
         
+        // Bootstrap the operational roles; hand them to dedicated accounts
+        // with grant_role and revoke them from the admin afterwards.
+        // Whatever the admin still holds moves with it in set_admin.
+        for role in [Role::Issuer, Role::ComplianceOfficer, Role::ValuationAgent] {
+            env.storage().persistent().set(&RWAKey::RoleMember(role, admin.clone()), &true);
+        }
+        
         env.events().publish(
             (Symbol::new(&env, "RWAInitialized"),),
             admin,
@@ -145,6 +855,21 @@ impl RWAContract {
         
         env.storage().persistent().set(&RWAKey::Admin, &new_admin);
         
+        // The outgoing admin keeps no operational role
+        for role in [
+            Role::PlatformAdmin,
+            Role::Issuer,
+            Role::ComplianceOfficer,
+            Role::ValuationAgent,
+            Role::Custodian,
+        ] {
+            let key = RWAKey::RoleMember(role.clone(), admin.clone());
+            if env.storage().persistent().has(&key) {
+                env.storage().persistent().remove(&key);
+                env.storage().persistent().set(&RWAKey::RoleMember(role, new_admin.clone()), &true);
+            }
+        }
+        
         env.events().publish(
             (Symbol::new(&env, "AdminUpdated"),),
             new_admin,
@@ -152,11 +877,8 @@ impl RWAContract {
     }
     
     /// Whitelist a country for investment
-    pub fn whitelist_country(env: Env, country_code: String, allowed: bool) {
-        let admin: Address = env.storage().persistent()
-            .get(&RWAKey::Admin)
-            .expect("Contract not initialized");
-        admin.require_auth();
+    pub fn whitelist_country(env: Env, caller: Address, country_code: String, allowed: bool) {
+        require_role(&env, &caller, Role::PlatformAdmin);
         
         env.storage().persistent().set(&RWAKey::WhitelistedCountry(country_code.clone()), &allowed);
         
@@ -167,11 +889,8 @@ impl RWAContract {
     }
     
     /// Blacklist an address
-    pub fn blacklist_address(env: Env, address: Address, blacklisted: bool) {
-        let admin: Address = env.storage().persistent()
-            .get(&RWAKey::Admin)
-            .expect("Contract not initialized");
-        admin.require_auth();
+    pub fn blacklist_address(env: Env, caller: Address, address: Address, blacklisted: bool) {
+        require_role(&env, &caller, Role::ComplianceOfficer);
         
         env.storage().persistent().set(&RWAKey::BlacklistedAddress(address.clone()), &blacklisted);
         
@@ -181,11 +900,49 @@ impl RWAContract {
         );
     }
     
+    /// Grant a role to an account
+    pub fn grant_role(env: Env, caller: Address, role: Role, account: Address) {
+        require_role(&env, &caller, Role::PlatformAdmin);
+        
+        env.storage().persistent().set(&RWAKey::RoleMember(role.clone(), account.clone()), &true);
+        
+        env.events().publish(
+            (Symbol::new(&env, "RoleGranted"),),
+            (role, account, caller),
+        );
+    }
+    
+    /// Revoke a role from an account. The primary admin stays a platform
+    /// admin until replaced with `set_admin`.
+    pub fn revoke_role(env: Env, caller: Address, role: Role, account: Address) {
+        require_role(&env, &caller, Role::PlatformAdmin);
+        
+        env.storage().persistent().remove(&RWAKey::RoleMember(role.clone(), account.clone()));
+        
+        env.events().publish(
+            (Symbol::new(&env, "RoleRevoked"),),
+            (role, account, caller),
+        );
+    }
+    
+    /// Set how long holders have to claim new distributions
+    pub fn set_claim_period(env: Env, caller: Address, claim_period: u64) {
+        require_role(&env, &caller, Role::PlatformAdmin);
+        
+        env.storage().persistent().set(&RWAKey::ClaimPeriod, &claim_period);
+        
+        env.events().publish(
+            (Symbol::new(&env, "ClaimPeriodUpdated"),),
+            claim_period,
+        );
+    }
+    
     // ============== Asset Management ==============
     
-    /// Create/tokenize a new real-world asset
+    /// Create/tokenize a new real-world asset issued by `issuer`
     pub fn create_asset(
         env: Env,
+        issuer: Address,
         name: String,
         symbol: String,
         asset_type: AssetType,
@@ -193,18 +950,19 @@ impl RWAContract {
         asset_value_usd: i128,
         custodian: Address,
         token_address: Address,
-        min_investment: i128,
-        accredited_only: bool,
+        compliance: ComplianceTerms,
     ) -> u64 {
-        let admin: Address = env.storage().persistent()
-            .get(&RWAKey::Admin)
-            .expect("Contract not initialized");
-        admin.require_auth();
+        require_role(&env, &issuer, Role::Issuer);
         
         if total_supply <= 0 || asset_value_usd <= 0 {
             panic!("Invalid supply or value");
         }
         
+        // Only a custodian can later pause the asset with set_asset_active
+        if !holds_role(&env, Role::Custodian, &custodian) {
+            panic!("Custodian role required");
+        }
+        
         // Get next asset ID
         let asset_id: u64 = env.storage().persistent()
             .get(&RWAKey::AssetCounter)
@@ -217,21 +975,22 @@ impl RWAContract {
             asset_type: asset_type.clone(),
             total_supply,
             circulating_supply: 0,
-            issuer: admin.clone(),
+            issuer: issuer.clone(),
             custodian: custodian.clone(),
             asset_value_usd,
             created_at: env.ledger().timestamp(),
             last_valuation: env.ledger().timestamp(),
             is_active: true,
             is_transferable: true,
-            min_investment,
-            accredited_only,
+            min_investment: compliance.min_investment,
+            accredited_only: compliance.accredited_only,
         };
         
         // Store asset
         env.storage().persistent().set(&RWAKey::Asset(asset_id), &asset);
         env.storage().persistent().set(&RWAKey::AssetToken(asset_id), &token_address);
         env.storage().persistent().set(&RWAKey::AssetCounter, &asset_id);
+        push_valuation(&env, asset_id, asset_value_usd, 0);
         
         // Update TVL
         let tvl: i128 = env.storage().persistent()
@@ -247,23 +1006,118 @@ impl RWAContract {
         asset_id
     }
     
-    /// Update asset valuation
-    pub fn update_valuation(env: Env, asset_id: u64, new_value_usd: i128) -> bool {
-        let admin: Address = env.storage().persistent()
-            .get(&RWAKey::Admin)
-            .expect("Contract not initialized");
-        admin.require_auth();
+    /// Set how appraisals of an asset are accepted
+    pub fn set_valuation_config(
+        env: Env,
+        caller: Address,
+        asset_id: u64,
+        quorum: u32,
+        tolerance_bps: u32,
+        max_deviation_bps: u32,
+        staleness_window: u64,
+    ) {
+        require_role(&env, &caller, Role::PlatformAdmin);
+        
+        if !env.storage().persistent().has(&RWAKey::Asset(asset_id)) {
+            panic!("Asset not found");
+        }
+        if quorum == 0 || tolerance_bps as i128 > BPS_DENOMINATOR {
+            panic!("Invalid valuation config");
+        }
         
-        let mut asset: RWAAsset = match env.storage().persistent().get(&RWAKey::Asset(asset_id)) {
-            Some(a) => a,
-            None => return false,
-        };
+        let config = ValuationConfig { quorum, tolerance_bps, max_deviation_bps, staleness_window };
+        env.storage().persistent().set(&RWAKey::ValuationConfig(asset_id), &config);
         
+        env.events().publish(
+            (Symbol::new(&env, "ValuationConfigUpdated"),),
+            (asset_id, quorum, tolerance_bps, max_deviation_bps, staleness_window),
+        );
+    }
+    
+    /// Submit an appraisal. Once a quorum of appraisals lies within the
+    /// tolerance band around their median, the median becomes the asset's
+    /// value, provided it moves less than the maximum deviation. The asset
+    /// needs a config from `set_valuation_config` first. Returns whether a
+    /// new valuation was applied.
+    pub fn submit_valuation(env: Env, appraiser: Address, asset_id: u64, value_usd: i128) -> bool {
+        require_role(&env, &appraiser, Role::ValuationAgent);
+        
+        let mut asset: RWAAsset = match env.storage().persistent().get(&RWAKey::Asset(asset_id)) {
+            Some(a) => a,
+            None => return false,
+        };
+        
+        if value_usd <= 0 {
+            panic!("Invalid valuation");
+        }
+        
+        let config: ValuationConfig = match env.storage().persistent().get(&RWAKey::ValuationConfig(asset_id)) {
+            Some(c) => c,
+            None => panic!("Valuation not configured"),
+        };
+        let now = env.ledger().timestamp();
+        
+        // Keep each appraiser's latest submission, dropping stale ones
+        let round: Vec<ValuationSubmission> = env.storage().persistent()
+            .get(&RWAKey::ValuationRound(asset_id))
+            .unwrap_or(Vec::new(&env));
+        let mut submissions = Vec::new(&env);
+        for submission in round.iter() {
+            let expired = config.staleness_window > 0 && submission.submitted_at + config.staleness_window < now;
+            if submission.appraiser != appraiser && !expired {
+                submissions.push_back(submission);
+            }
+        }
+        submissions.push_back(ValuationSubmission { appraiser: appraiser.clone(), value_usd, submitted_at: now });
+        
+        env.events().publish(
+            (Symbol::new(&env, "ValuationSubmitted"),),
+            (asset_id, appraiser, value_usd),
+        );
+        
+        if submissions.len() < config.quorum {
+            env.storage().persistent().set(&RWAKey::ValuationRound(asset_id), &submissions);
+            return false;
+        }
+        
+        // Appraisals inside the tolerance band around the median
+        let mut values = Vec::new(&env);
+        for submission in submissions.iter() {
+            values.push_back(submission.value_usd);
+        }
+        let midpoint = median(&env, &values);
+        let mut agreeing = Vec::new(&env);
+        for value in values.iter() {
+            if (value - midpoint).abs() * BPS_DENOMINATOR <= midpoint * config.tolerance_bps as i128 {
+                agreeing.push_back(value);
+            }
+        }
+        
+        if agreeing.len() < config.quorum {
+            env.storage().persistent().set(&RWAKey::ValuationRound(asset_id), &submissions);
+            return false;
+        }
+        
+        let new_value_usd = median(&env, &agreeing);
         let old_value = asset.asset_value_usd;
+        env.storage().persistent().remove(&RWAKey::ValuationRound(asset_id));
+        
+        // Out-of-bounds moves are discarded and must be appraised again
+        if config.max_deviation_bps > 0
+            && (new_value_usd - old_value).abs() * BPS_DENOMINATOR > old_value * config.max_deviation_bps as i128
+        {
+            env.events().publish(
+                (Symbol::new(&env, "ValuationRejected"),),
+                (asset_id, old_value, new_value_usd),
+            );
+            return false;
+        }
+        
         asset.asset_value_usd = new_value_usd;
-        asset.last_valuation = env.ledger().timestamp();
+        asset.last_valuation = now;
         
         env.storage().persistent().set(&RWAKey::Asset(asset_id), &asset);
+        push_valuation(&env, asset_id, new_value_usd, agreeing.len());
         
         // Update TVL
         let tvl: i128 = env.storage().persistent()
@@ -280,11 +1134,8 @@ impl RWAContract {
     }
     
     /// Pause/unpause asset transfers
-    pub fn set_asset_transferable(env: Env, asset_id: u64, transferable: bool) -> bool {
-        let admin: Address = env.storage().persistent()
-            .get(&RWAKey::Admin)
-            .expect("Contract not initialized");
-        admin.require_auth();
+    pub fn set_asset_transferable(env: Env, caller: Address, asset_id: u64, transferable: bool) -> bool {
+        require_role(&env, &caller, Role::ComplianceOfficer);
         
         let mut asset: RWAAsset = match env.storage().persistent().get(&RWAKey::Asset(asset_id)) {
             Some(a) => a,
@@ -302,20 +1153,214 @@ impl RWAContract {
         true
     }
     
+    /// Custodian confirms or suspends custody of the underlying asset
+    pub fn set_asset_active(env: Env, asset_id: u64, is_active: bool) -> bool {
+        let mut asset: RWAAsset = match env.storage().persistent().get(&RWAKey::Asset(asset_id)) {
+            Some(a) => a,
+            None => return false,
+        };
+        
+        asset.custodian.require_auth();
+        if !holds_role(&env, Role::Custodian, &asset.custodian) {
+            panic!("Missing required role");
+        }
+        
+        asset.is_active = is_active;
+        env.storage().persistent().set(&RWAKey::Asset(asset_id), &asset);
+        
+        env.events().publish(
+            (Symbol::new(&env, "AssetActiveChanged"),),
+            (asset_id, is_active),
+        );
+        
+        true
+    }
+    
+    /// Set the lock-up applied to tokens bought through `invest`
+    pub fn set_lockup_period(env: Env, asset_id: u64, lockup_period: u64) {
+        let asset: RWAAsset = env.storage().persistent()
+            .get(&RWAKey::Asset(asset_id))
+            .expect("Asset not found");
+        require_issuer(&env, &asset);
+        
+        env.storage().persistent().set(&RWAKey::LockupPeriod(asset_id), &lockup_period);
+        
+        env.events().publish(
+            (Symbol::new(&env, "LockupPeriodUpdated"),),
+            (asset_id, lockup_period),
+        );
+    }
+    
+    /// Release all of an investor's lock-ups in an asset early. This is a
+    /// platform admin override, not a routine compliance action.
+    pub fn release_lockup(env: Env, caller: Address, asset_id: u64, investor_address: Address) {
+        require_role(&env, &caller, Role::PlatformAdmin);
+        
+        let released = locked_balance(&env, asset_id, &investor_address);
+        env.storage().persistent().remove(&RWAKey::Lockups(asset_id, investor_address.clone()));
+        
+        if let Some(mut holding) = env.storage().persistent()
+            .get::<_, Holding>(&RWAKey::Holding(asset_id, investor_address.clone()))
+        {
+            holding.locked_until = 0;
+            env.storage().persistent().set(&RWAKey::Holding(asset_id, investor_address.clone()), &holding);
+        }
+        
+        env.events().publish(
+            (Symbol::new(&env, "LockupReleased"),),
+            (asset_id, investor_address, released),
+        );
+    }
+    
+    /// Set an asset's allowed and blocked countries and holder limit
+    pub fn set_jurisdiction_rules(
+        env: Env,
+        caller: Address,
+        asset_id: u64,
+        allowed_countries: Vec<String>,
+        blocked_countries: Vec<String>,
+        max_holders: u32,
+    ) {
+        require_role(&env, &caller, Role::ComplianceOfficer);
+        
+        if !env.storage().persistent().has(&RWAKey::Asset(asset_id)) {
+            panic!("Asset not found");
+        }
+        
+        let rules = JurisdictionRules { allowed_countries, blocked_countries, max_holders };
+        env.storage().persistent().set(&RWAKey::JurisdictionRules(asset_id), &rules);
+        
+        env.events().publish(
+            (Symbol::new(&env, "JurisdictionRulesUpdated"),),
+            (asset_id, max_holders),
+        );
+    }
+    
+    /// Cap the holders of an asset from one country (0 removes the cap)
+    pub fn set_country_holder_cap(env: Env, caller: Address, asset_id: u64, country_code: String, cap: u32) {
+        require_role(&env, &caller, Role::ComplianceOfficer);
+        
+        let key = RWAKey::CountryHolderCap(asset_id, country_code.clone());
+        if cap == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &cap);
+        }
+        
+        env.events().publish(
+            (Symbol::new(&env, "CountryHolderCapUpdated"),),
+            (asset_id, country_code, cap),
+        );
+    }
+    
+    // ============== Document Registry ==============
+    
+    /// Add or replace a named document of an asset
+    pub fn set_document(
+        env: Env,
+        asset_id: u64,
+        name: String,
+        uri: String,
+        content_hash: BytesN<32>,
+        requires_acknowledgement: bool,
+    ) {
+        let asset: RWAAsset = env.storage().persistent()
+            .get(&RWAKey::Asset(asset_id))
+            .expect("Asset not found");
+        require_issuer(&env, &asset);
+        
+        let key = RWAKey::Document(asset_id, name.clone());
+        if !env.storage().persistent().has(&key) {
+            let mut names: Vec<String> = env.storage().persistent()
+                .get(&RWAKey::DocumentNames(asset_id))
+                .unwrap_or(Vec::new(&env));
+            names.push_back(name.clone());
+            env.storage().persistent().set(&RWAKey::DocumentNames(asset_id), &names);
+        }
+        
+        let document = Document {
+            name: name.clone(),
+            uri: uri.clone(),
+            content_hash: content_hash.clone(),
+            last_modified: env.ledger().timestamp(),
+            requires_acknowledgement,
+        };
+        env.storage().persistent().set(&key, &document);
+        
+        env.events().publish(
+            (Symbol::new(&env, "DocumentUpdated"),),
+            (asset_id, name, uri, content_hash),
+        );
+    }
+    
+    /// Remove a document from an asset
+    pub fn remove_document(env: Env, asset_id: u64, name: String) {
+        let asset: RWAAsset = env.storage().persistent()
+            .get(&RWAKey::Asset(asset_id))
+            .expect("Asset not found");
+        require_issuer(&env, &asset);
+        
+        let key = RWAKey::Document(asset_id, name.clone());
+        if !env.storage().persistent().has(&key) {
+            panic!("Document not found");
+        }
+        env.storage().persistent().remove(&key);
+        
+        let mut names: Vec<String> = env.storage().persistent()
+            .get(&RWAKey::DocumentNames(asset_id))
+            .unwrap_or(Vec::new(&env));
+        if let Some(position) = names.first_index_of(name.clone()) {
+            names.remove(position);
+        }
+        env.storage().persistent().set(&RWAKey::DocumentNames(asset_id), &names);
+        
+        env.events().publish(
+            (Symbol::new(&env, "DocumentRemoved"),),
+            (asset_id, name),
+        );
+    }
+    
+    /// Investor confirms having read a document. The hash must match the
+    /// current version, so a replaced document needs a fresh acknowledgement.
+    pub fn acknowledge_document(
+        env: Env,
+        asset_id: u64,
+        investor_address: Address,
+        name: String,
+        content_hash: BytesN<32>,
+    ) {
+        investor_address.require_auth();
+        
+        let document: Document = env.storage().persistent()
+            .get(&RWAKey::Document(asset_id, name.clone()))
+            .expect("Document not found");
+        if document.content_hash != content_hash {
+            panic!("Document hash mismatch");
+        }
+        
+        env.storage().persistent().set(
+            &RWAKey::Acknowledgement(asset_id, name.clone(), investor_address.clone()),
+            &content_hash,
+        );
+        
+        env.events().publish(
+            (Symbol::new(&env, "DocumentAcknowledged"),),
+            (asset_id, investor_address, name, content_hash),
+        );
+    }
+    
     // ============== Investor Management ==============
     
     /// Register a new investor with KYC
     pub fn register_investor(
         env: Env,
+        caller: Address,
         investor_address: Address,
         is_accredited: bool,
         country_code: String,
         kyc_expiry: u64,
     ) -> bool {
-        let admin: Address = env.storage().persistent()
-            .get(&RWAKey::Admin)
-            .expect("Contract not initialized");
-        admin.require_auth();
+        require_role(&env, &caller, Role::ComplianceOfficer);
         
         // Check country whitelist
         let country_allowed: bool = env.storage().persistent()
@@ -326,6 +1371,19 @@ impl RWAContract {
             panic!("Country not whitelisted");
         }
         
+        // Per-country holder counts follow the country on record, so it
+        // cannot change under an investor who still holds tokens
+        if let Some(existing) = env.storage().persistent()
+            .get::<_, Investor>(&RWAKey::Investor(investor_address.clone()))
+        {
+            let held: u32 = env.storage().persistent()
+                .get(&RWAKey::HeldAssetCount(investor_address.clone()))
+                .unwrap_or(0);
+            if existing.country_code != country_code && held > 0 {
+                panic!("Cannot change country while holding assets");
+            }
+        }
+        
         let investor = Investor {
             address: investor_address.clone(),
             is_accredited,
@@ -347,11 +1405,8 @@ impl RWAContract {
     }
     
     /// Update investor accreditation status
-    pub fn update_accreditation(env: Env, investor_address: Address, is_accredited: bool) -> bool {
-        let admin: Address = env.storage().persistent()
-            .get(&RWAKey::Admin)
-            .expect("Contract not initialized");
-        admin.require_auth();
+    pub fn update_accreditation(env: Env, caller: Address, investor_address: Address, is_accredited: bool) -> bool {
+        require_role(&env, &caller, Role::ComplianceOfficer);
         
         let mut investor: Investor = match env.storage().persistent().get(&RWAKey::Investor(investor_address.clone())) {
             Some(i) => i,
@@ -400,6 +1455,12 @@ impl RWAContract {
             panic!("Asset is not active");
         }
         
+        // Block purchases at an out-of-date price
+        let config = valuation_config(&env, asset_id);
+        if config.staleness_window > 0 && asset.last_valuation + config.staleness_window < env.ledger().timestamp() {
+            panic!("Valuation is stale");
+        }
+        
         // Check minimum investment
         if amount < asset.min_investment {
             panic!("Below minimum investment");
@@ -425,6 +1486,9 @@ impl RWAContract {
             panic!("Accredited investors only");
         }
         
+        require_jurisdiction(&env, asset_id, &investor);
+        require_acknowledgements(&env, asset_id, &investor_address);
+        
         // Transfer payment
         let payment_client = token::Client::new(&env, &payment_token);
         payment_client.transfer(&investor_address, &asset.custodian, &payment_amount);
@@ -440,9 +1504,12 @@ impl RWAContract {
         let existing_holding: Option<Holding> = env.storage().persistent()
             .get(&RWAKey::Holding(asset_id, investor_address.clone()));
         
+        let previous_balance = existing_holding.as_ref().map(|h| h.amount).unwrap_or(0);
+        settle_rewards(&env, asset_id, &investor_address, previous_balance);
+        
         let price_per_token = payment_amount / amount;
         
-        let holding = match existing_holding {
+        let mut holding = match existing_holding {
             Some(mut h) => {
                 h.amount += amount;
                 h.purchase_price = (h.purchase_price + price_per_token) / 2; // Average price
@@ -458,7 +1525,31 @@ impl RWAContract {
             }
         };
         
+        // Each purchase gets its own lock-up so earlier tranches keep their dates
+        let lockup_period: u64 = env.storage().persistent()
+            .get(&RWAKey::LockupPeriod(asset_id))
+            .unwrap_or(0);
+        if lockup_period > 0 {
+            let now = env.ledger().timestamp();
+            let key = RWAKey::Lockups(asset_id, investor_address.clone());
+            let tranches: Vec<LockupTranche> = env.storage().persistent()
+                .get(&key)
+                .unwrap_or(Vec::new(&env));
+            
+            let mut active = Vec::new(&env);
+            for tranche in tranches.iter() {
+                if tranche.unlocks_at > now {
+                    active.push_back(tranche);
+                }
+            }
+            active.push_back(LockupTranche { amount, unlocks_at: now + lockup_period });
+            env.storage().persistent().set(&key, &active);
+            holding.locked_until = now + lockup_period;
+        }
+        
         env.storage().persistent().set(&RWAKey::Holding(asset_id, investor_address.clone()), &holding);
+        write_checkpoint(&env, asset_id, &investor_address, holding.amount);
+        track_holder(&env, asset_id, &investor_address, previous_balance, holding.amount);
         
         // Update asset circulating supply
         asset.circulating_supply += amount;
@@ -523,6 +1614,8 @@ impl RWAContract {
             panic!("Recipient must be accredited");
         }
         
+        require_jurisdiction(&env, asset_id, &to_investor);
+        
         // Check sender holding and lock-up
         let mut from_holding: Holding = match env.storage().persistent().get(&RWAKey::Holding(asset_id, from.clone())) {
             Some(h) => h,
@@ -533,8 +1626,8 @@ impl RWAContract {
             panic!("Insufficient balance");
         }
         
-        if from_holding.locked_until > env.ledger().timestamp() {
-            panic!("Tokens are locked");
+        if spendable_balance(&env, asset_id, &from, from_holding.amount) < amount {
+            panic!("Tokens are locked or frozen");
         }
         
         // Transfer tokens
@@ -545,8 +1638,11 @@ impl RWAContract {
         asset_client.transfer(&from, &to, &amount);
         
         // Update sender holding
+        settle_rewards(&env, asset_id, &from, from_holding.amount);
         from_holding.amount -= amount;
         env.storage().persistent().set(&RWAKey::Holding(asset_id, from.clone()), &from_holding);
+        write_checkpoint(&env, asset_id, &from, from_holding.amount);
+        track_holder(&env, asset_id, &from, from_holding.amount + amount, from_holding.amount);
         
         // Update recipient holding
         let mut to_holding: Holding = env.storage().persistent()
@@ -560,8 +1656,11 @@ impl RWAContract {
                 locked_until: 0,
             });
         
+        settle_rewards(&env, asset_id, &to, to_holding.amount);
         to_holding.amount += amount;
         env.storage().persistent().set(&RWAKey::Holding(asset_id, to.clone()), &to_holding);
+        write_checkpoint(&env, asset_id, &to, to_holding.amount);
+        track_holder(&env, asset_id, &to, to_holding.amount - amount, to_holding.amount);
         
         env.events().publish(
             (Symbol::new(&env, "Transfer"),),
@@ -571,6 +1670,300 @@ impl RWAContract {
         true
     }
     
+    // ============== Compliance Actions ==============
+    
+    /// Move tokens between investors without the sender's signature, e.g.
+    /// under a court order. The recipient must pass KYC and the asset's
+    /// jurisdiction rules; lock-ups travel with the moved tokens.
+    pub fn forced_transfer(
+        env: Env,
+        agent: Address,
+        asset_id: u64,
+        from: Address,
+        to: Address,
+        amount: i128,
+        reason_code: u32,
+    ) -> bool {
+        require_role(&env, &agent, Role::ComplianceOfficer);
+        
+        if amount <= 0 {
+            panic!("Invalid amount");
+        }
+        if from == to {
+            panic!("Cannot transfer to self");
+        }
+        
+        let asset: RWAAsset = match env.storage().persistent().get(&RWAKey::Asset(asset_id)) {
+            Some(a) => a,
+            None => panic!("Asset not found"),
+        };
+        
+        let to_blacklisted: bool = env.storage().persistent()
+            .get(&RWAKey::BlacklistedAddress(to.clone()))
+            .unwrap_or(false);
+        if to_blacklisted {
+            panic!("Address is blacklisted");
+        }
+        
+        let to_investor: Investor = match env.storage().persistent().get(&RWAKey::Investor(to.clone())) {
+            Some(i) => i,
+            None => panic!("Recipient not registered"),
+        };
+        
+        if !to_investor.is_kyc_verified || to_investor.kyc_expiry < env.ledger().timestamp() {
+            panic!("Recipient KYC verification required");
+        }
+        
+        if asset.accredited_only && !to_investor.is_accredited {
+            panic!("Recipient must be accredited");
+        }
+        
+        require_jurisdiction(&env, asset_id, &to_investor);
+        
+        let mut from_holding: Holding = match env.storage().persistent().get(&RWAKey::Holding(asset_id, from.clone())) {
+            Some(h) => h,
+            None => panic!("No holding found"),
+        };
+        
+        if from_holding.amount < amount {
+            panic!("Insufficient balance");
+        }
+        
+        // Free tokens go first; whatever comes out of the restricted part
+        // releases the sender's freeze by the same amount
+        let from_restricted = amount - spendable_balance(&env, asset_id, &from, from_holding.amount);
+        
+        reassign_tokens(&env, asset_id, &from, &to, amount);
+        move_lockups(&env, asset_id, &from, &to, from_holding.amount, amount);
+        
+        // Rewards earned so far stay with the sender
+        settle_rewards(&env, asset_id, &from, from_holding.amount);
+        from_holding.amount -= amount;
+        env.storage().persistent().set(&RWAKey::Holding(asset_id, from.clone()), &from_holding);
+        write_checkpoint(&env, asset_id, &from, from_holding.amount);
+        track_holder(&env, asset_id, &from, from_holding.amount + amount, from_holding.amount);
+        
+        let frozen = frozen_balance(&env, asset_id, &from);
+        if from_restricted > 0 && frozen > 0 {
+            env.storage().persistent()
+                .set(&RWAKey::FrozenAmount(asset_id, from.clone()), &(frozen - from_restricted.min(frozen)));
+        }
+        
+        let mut to_holding: Holding = env.storage().persistent()
+            .get(&RWAKey::Holding(asset_id, to.clone()))
+            .unwrap_or(Holding {
+                asset_id,
+                investor: to.clone(),
+                amount: 0,
+                purchase_price: 0,
+                acquired_at: env.ledger().timestamp(),
+                locked_until: 0,
+            });
+        
+        settle_rewards(&env, asset_id, &to, to_holding.amount);
+        to_holding.amount += amount;
+        to_holding.locked_until = to_holding.locked_until.max(from_holding.locked_until);
+        env.storage().persistent().set(&RWAKey::Holding(asset_id, to.clone()), &to_holding);
+        write_checkpoint(&env, asset_id, &to, to_holding.amount);
+        track_holder(&env, asset_id, &to, to_holding.amount - amount, to_holding.amount);
+        
+        env.events().publish(
+            (Symbol::new(&env, "ForcedTransfer"),),
+            (asset_id, from, to, amount, reason_code, agent),
+        );
+        
+        true
+    }
+    
+    /// Move everything a lost wallet holds to a replacement wallet: holdings,
+    /// lock-ups, freezes, unclaimed rewards, queued redemptions, document
+    /// acknowledgements and the investor record. `asset_ids` must cover
+    /// every asset the wallet holds. The new wallet must not already be
+    /// registered. Returns the number of assets migrated.
+    pub fn recover_address(
+        env: Env,
+        agent: Address,
+        lost_wallet: Address,
+        new_wallet: Address,
+        asset_ids: Vec<u64>,
+        reason_code: u32,
+    ) -> u32 {
+        require_role(&env, &agent, Role::ComplianceOfficer);
+        
+        if lost_wallet == new_wallet {
+            panic!("Cannot recover to the same address");
+        }
+        
+        let mut investor: Investor = match env.storage().persistent().get(&RWAKey::Investor(lost_wallet.clone())) {
+            Some(i) => i,
+            None => panic!("Investor not registered"),
+        };
+        
+        if env.storage().persistent().has(&RWAKey::Investor(new_wallet.clone())) {
+            panic!("Address already registered");
+        }
+        
+        let new_blacklisted: bool = env.storage().persistent()
+            .get(&RWAKey::BlacklistedAddress(new_wallet.clone()))
+            .unwrap_or(false);
+        if new_blacklisted {
+            panic!("Address is blacklisted");
+        }
+        
+        let mut migrated = 0;
+        let mut held_migrated: u32 = 0;
+        for asset_id in asset_ids.iter() {
+            let mut holding: Holding = match env.storage().persistent().get(&RWAKey::Holding(asset_id, lost_wallet.clone())) {
+                Some(h) => h,
+                None => continue,
+            };
+            
+            if holding.amount > 0 {
+                reassign_tokens(&env, asset_id, &lost_wallet, &new_wallet, holding.amount);
+                held_migrated += 1;
+            }
+            
+            // Bring rewards up to date, then hand the unclaimed balance over
+            settle_rewards(&env, asset_id, &lost_wallet, holding.amount);
+            let tokens: Vec<Address> = env.storage().persistent()
+                .get(&RWAKey::RewardTokens(asset_id))
+                .unwrap_or(Vec::new(&env));
+            for token in tokens.iter() {
+                let old_key = RWAKey::Reward(asset_id, token.clone(), lost_wallet.clone());
+                if let Some(state) = env.storage().persistent().get::<_, RewardState>(&old_key) {
+                    move_credits(&env, asset_id, &token, &lost_wallet, &new_wallet, state.claimed_index);
+                    env.storage().persistent().set(&RWAKey::Reward(asset_id, token.clone(), new_wallet.clone()), &state);
+                    env.storage().persistent().remove(&old_key);
+                }
+            }
+            
+            let lockup_key = RWAKey::Lockups(asset_id, lost_wallet.clone());
+            if let Some(tranches) = env.storage().persistent().get::<_, Vec<LockupTranche>>(&lockup_key) {
+                env.storage().persistent().set(&RWAKey::Lockups(asset_id, new_wallet.clone()), &tranches);
+                env.storage().persistent().remove(&lockup_key);
+            }
+            
+            let frozen_key = RWAKey::FrozenAmount(asset_id, lost_wallet.clone());
+            if let Some(frozen) = env.storage().persistent().get::<_, i128>(&frozen_key) {
+                env.storage().persistent().set(&RWAKey::FrozenAmount(asset_id, new_wallet.clone()), &frozen);
+                env.storage().persistent().remove(&frozen_key);
+            }
+            
+            let names: Vec<String> = env.storage().persistent()
+                .get(&RWAKey::DocumentNames(asset_id))
+                .unwrap_or(Vec::new(&env));
+            for name in names.iter() {
+                let ack_key = RWAKey::Acknowledgement(asset_id, name.clone(), lost_wallet.clone());
+                if let Some(hash) = env.storage().persistent().get::<_, BytesN<32>>(&ack_key) {
+                    env.storage().persistent().set(&RWAKey::Acknowledgement(asset_id, name, new_wallet.clone()), &hash);
+                    env.storage().persistent().remove(&ack_key);
+                }
+            }
+            
+            // Pending redemptions pay out to the new wallet
+            let queue: Vec<u64> = env.storage().persistent()
+                .get(&RWAKey::RedemptionQueue(asset_id))
+                .unwrap_or(Vec::new(&env));
+            for request_id in queue.iter() {
+                let mut request: RedemptionRequest = env.storage().persistent()
+                    .get(&RWAKey::Redemption(request_id))
+                    .expect("Redemption not found");
+                if request.investor == lost_wallet {
+                    request.investor = new_wallet.clone();
+                    env.storage().persistent().set(&RWAKey::Redemption(request_id), &request);
+                }
+            }
+            
+            // Holder counts are unchanged: same investor, same country
+            let balance = holding.amount;
+            holding.investor = new_wallet.clone();
+            env.storage().persistent().set(&RWAKey::Holding(asset_id, new_wallet.clone()), &holding);
+            env.storage().persistent().remove(&RWAKey::Holding(asset_id, lost_wallet.clone()));
+            write_checkpoint(&env, asset_id, &lost_wallet, 0);
+            write_checkpoint(&env, asset_id, &new_wallet, balance);
+            
+            env.events().publish(
+                (Symbol::new(&env, "HoldingRecovered"),),
+                (asset_id, lost_wallet.clone(), new_wallet.clone(), balance, reason_code),
+            );
+            
+            migrated += 1;
+        }
+        
+        // Holdings left behind would be stranded on an unregistered wallet
+        let held_key = RWAKey::HeldAssetCount(lost_wallet.clone());
+        let held: u32 = env.storage().persistent().get(&held_key).unwrap_or(0);
+        if held_migrated < held {
+            panic!("Every held asset must be migrated");
+        }
+        if held > 0 {
+            env.storage().persistent().set(&RWAKey::HeldAssetCount(new_wallet.clone()), &held);
+            env.storage().persistent().remove(&held_key);
+        }
+        
+        investor.address = new_wallet.clone();
+        env.storage().persistent().set(&RWAKey::Investor(new_wallet.clone()), &investor);
+        env.storage().persistent().remove(&RWAKey::Investor(lost_wallet.clone()));
+        
+        env.events().publish(
+            (Symbol::new(&env, "AddressRecovered"),),
+            (lost_wallet, new_wallet, migrated, reason_code, agent),
+        );
+        
+        migrated
+    }
+    
+    /// Freeze part of an investor's holding, e.g. one under a lien
+    pub fn freeze_partial(env: Env, agent: Address, asset_id: u64, investor_address: Address, amount: i128) {
+        require_role(&env, &agent, Role::ComplianceOfficer);
+        
+        if amount <= 0 {
+            panic!("Invalid amount");
+        }
+        
+        if !env.storage().persistent().has(&RWAKey::Asset(asset_id)) {
+            panic!("Asset not found");
+        }
+        
+        let balance = env.storage().persistent()
+            .get::<_, Holding>(&RWAKey::Holding(asset_id, investor_address.clone()))
+            .map(|h| h.amount)
+            .unwrap_or(0);
+        let frozen = frozen_balance(&env, asset_id, &investor_address) + amount;
+        if frozen > balance {
+            panic!("Amount exceeds balance");
+        }
+        
+        env.storage().persistent().set(&RWAKey::FrozenAmount(asset_id, investor_address.clone()), &frozen);
+        
+        env.events().publish(
+            (Symbol::new(&env, "TokensFrozen"),),
+            (asset_id, investor_address, amount, frozen, agent),
+        );
+    }
+    
+    /// Release part of a partial freeze
+    pub fn unfreeze_partial(env: Env, agent: Address, asset_id: u64, investor_address: Address, amount: i128) {
+        require_role(&env, &agent, Role::ComplianceOfficer);
+        
+        let frozen = frozen_balance(&env, asset_id, &investor_address);
+        if amount <= 0 || amount > frozen {
+            panic!("Invalid amount");
+        }
+        
+        let key = RWAKey::FrozenAmount(asset_id, investor_address.clone());
+        if frozen == amount {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &(frozen - amount));
+        }
+        
+        env.events().publish(
+            (Symbol::new(&env, "TokensUnfrozen"),),
+            (asset_id, investor_address, amount, frozen - amount, agent),
+        );
+    }
+    
     // ============== Dividend Distribution ==============
     
     /// Create a dividend/yield distribution for asset holders
@@ -580,15 +1973,11 @@ impl RWAContract {
         total_amount: i128,
         distribution_token: Address,
     ) -> u64 {
-        let admin: Address = env.storage().persistent()
-            .get(&RWAKey::Admin)
-            .expect("Contract not initialized");
-        admin.require_auth();
-        
         let asset: RWAAsset = match env.storage().persistent().get(&RWAKey::Asset(asset_id)) {
             Some(a) => a,
             None => panic!("Asset not found"),
         };
+        require_issuer(&env, &asset);
         
         if asset.circulating_supply == 0 {
             panic!("No tokens in circulation");
@@ -596,25 +1985,62 @@ impl RWAContract {
         
         // Transfer distribution tokens to contract
         let client = token::Client::new(&env, &distribution_token);
-        client.transfer(&admin, &env.current_contract_address(), &total_amount);
+        client.transfer(&asset.issuer, &env.current_contract_address(), &total_amount);
         
-        // Calculate per-token amount
+        // Calculate per-token amount (informational; payouts use the index)
         let per_token_amount = total_amount / asset.circulating_supply;
         
+        // Advance the fixed-point reward index. Whatever does not divide
+        // evenly is kept as dust and added to the next distribution.
+        let index_key = RWAKey::RewardIndex(asset_id, distribution_token.clone());
+        let dust_key = RWAKey::RewardDust(asset_id, distribution_token.clone());
+        let dust: i128 = env.storage().persistent().get(&dust_key).unwrap_or(0);
+        let scaled = total_amount * REWARD_SCALE + dust;
+        let index_delta = scaled / asset.circulating_supply;
+        let reward_index = env.storage().persistent().get::<_, i128>(&index_key).unwrap_or(0)
+            + index_delta;
+        env.storage().persistent().set(&index_key, &reward_index);
+        env.storage().persistent().set(&dust_key, &(scaled % asset.circulating_supply));
+        
+        let mut reward_tokens: Vec<Address> = env.storage().persistent()
+            .get(&RWAKey::RewardTokens(asset_id))
+            .unwrap_or(Vec::new(&env));
+        if !reward_tokens.contains(&distribution_token) {
+            reward_tokens.push_back(distribution_token.clone());
+            env.storage().persistent().set(&RWAKey::RewardTokens(asset_id), &reward_tokens);
+        }
+        
         // Get next distribution ID
         let distribution_id: u64 = env.storage().persistent()
             .get(&RWAKey::DistributionCounter)
             .unwrap_or(0) + 1;
         
+        let stream_key = RWAKey::RewardDistributions(asset_id, distribution_token.clone());
+        let mut stream: Vec<u64> = env.storage().persistent()
+            .get(&stream_key)
+            .unwrap_or(Vec::new(&env));
+        stream.push_back(distribution_id);
+        env.storage().persistent().set(&stream_key, &stream);
+        
+        let claim_period: u64 = env.storage().persistent()
+            .get(&RWAKey::ClaimPeriod)
+            .unwrap_or(DEFAULT_CLAIM_PERIOD);
+        
         let distribution = Distribution {
             distribution_id,
             asset_id,
             total_amount,
             per_token_amount,
             distribution_token: distribution_token.clone(),
+            reward_index,
+            index_delta,
+            eligible_supply: asset.circulating_supply,
             snapshot_time: env.ledger().timestamp(),
             created_at: env.ledger().timestamp(),
-            is_claimed: false,
+            claim_deadline: env.ledger().timestamp() + claim_period,
+            claimed: 0,
+            claimant_count: 0,
+            is_reclaimed: false,
         };
         
         env.storage().persistent().set(&RWAKey::Distribution(distribution_id), &distribution);
@@ -628,7 +2054,9 @@ impl RWAContract {
         distribution_id
     }
     
-    /// Claim dividend from a distribution
+    /// Claim dividend from a distribution. Pays everything accrued in the
+    /// distribution's token for its asset, which covers this distribution
+    /// and any earlier unclaimed ones in the same token.
     pub fn claim_distribution(
         env: Env,
         distribution_id: u64,
@@ -636,52 +2064,418 @@ impl RWAContract {
     ) -> i128 {
         investor_address.require_auth();
         
-        // Check if already claimed
-        let already_claimed: bool = env.storage().persistent()
-            .get(&RWAKey::ClaimedDistribution(distribution_id, investor_address.clone()))
-            .unwrap_or(false);
+        // Get distribution
+        let distribution: Distribution = match env.storage().persistent().get(&RWAKey::Distribution(distribution_id)) {
+            Some(d) => d,
+            None => panic!("Distribution not found"),
+        };
         
-        if already_claimed {
+        // Check if already claimed
+        if Self::is_distribution_claimed(env.clone(), distribution_id, investor_address.clone()) {
             panic!("Already claimed");
         }
         
-        // Get distribution
-        let distribution: Distribution = match env.storage().persistent().get(&RWAKey::Distribution(distribution_id)) {
+        if env.ledger().timestamp() > distribution.claim_deadline {
+            panic!("Claim period ended");
+        }
+        
+        let claim_amount = pay_reward(&env, distribution.asset_id, &distribution.distribution_token, &investor_address);
+        
+        if claim_amount <= 0 {
+            panic!("Nothing to claim");
+        }
+        
+        env.events().publish(
+            (Symbol::new(&env, "DistributionClaimed"),),
+            (distribution_id, investor_address, claim_amount),
+        );
+        
+        claim_amount
+    }
+    
+    /// Return what holders left unclaimed to the issuer once the claim
+    /// deadline has passed
+    pub fn reclaim_distribution(env: Env, distribution_id: u64) -> i128 {
+        let mut distribution: Distribution = match env.storage().persistent().get(&RWAKey::Distribution(distribution_id)) {
             Some(d) => d,
             None => panic!("Distribution not found"),
         };
         
-        // Get investor holding
-        let holding: Holding = match env.storage().persistent().get(&RWAKey::Holding(distribution.asset_id, investor_address.clone())) {
+        let asset: RWAAsset = env.storage().persistent()
+            .get(&RWAKey::Asset(distribution.asset_id))
+            .expect("Asset not found");
+        require_issuer(&env, &asset);
+        
+        if env.ledger().timestamp() <= distribution.claim_deadline {
+            panic!("Claim period not ended");
+        }
+        if distribution.is_reclaimed {
+            panic!("Already reclaimed");
+        }
+        
+        let remaining = distribution_remaining(&distribution);
+        distribution.is_reclaimed = true;
+        env.storage().persistent().set(&RWAKey::Distribution(distribution_id), &distribution);
+        
+        if remaining > 0 {
+            let client = token::Client::new(&env, &distribution.distribution_token);
+            client.transfer(&env.current_contract_address(), &asset.issuer, &remaining);
+        }
+        
+        env.events().publish(
+            (Symbol::new(&env, "DistributionReclaimed"),),
+            (distribution_id, asset.issuer, remaining),
+        );
+        
+        remaining
+    }
+    
+    /// Claim everything owed on an asset across all distribution tokens.
+    /// Returns the amount paid per token.
+    pub fn claim_all(env: Env, asset_id: u64, investor_address: Address) -> Map<Address, i128> {
+        investor_address.require_auth();
+        
+        let tokens: Vec<Address> = env.storage().persistent()
+            .get(&RWAKey::RewardTokens(asset_id))
+            .unwrap_or(Vec::new(&env));
+        
+        let mut paid = Map::new(&env);
+        for token in tokens.iter() {
+            let amount = pay_reward(&env, asset_id, &token, &investor_address);
+            if amount > 0 {
+                paid.set(token, amount);
+            }
+        }
+        
+        if paid.is_empty() {
+            panic!("Nothing to claim");
+        }
+        
+        env.events().publish(
+            (Symbol::new(&env, "RewardsClaimed"),),
+            (asset_id, investor_address, paid.clone()),
+        );
+        
+        paid
+    }
+    
+    // ============== Redemptions ==============
+    
+    /// Open, close or reconfigure an asset's redemption window
+    pub fn set_redemption_window(
+        env: Env,
+        asset_id: u64,
+        payment_token: Address,
+        fee_bps: u32,
+        is_open: bool,
+    ) {
+        let asset: RWAAsset = env.storage().persistent()
+            .get(&RWAKey::Asset(asset_id))
+            .expect("Asset not found");
+        require_issuer(&env, &asset);
+        
+        if fee_bps as i128 > BPS_DENOMINATOR {
+            panic!("Invalid redemption fee");
+        }
+        
+        // Funded liquidity is held in the current payment token
+        let existing: Option<RedemptionConfig> = env.storage().persistent()
+            .get(&RWAKey::RedemptionConfig(asset_id));
+        let liquidity: i128 = env.storage().persistent()
+            .get(&RWAKey::RedemptionLiquidity(asset_id))
+            .unwrap_or(0);
+        if let Some(config) = existing {
+            if config.payment_token != payment_token && liquidity > 0 {
+                panic!("Withdraw redemption liquidity first");
+            }
+        }
+        
+        let config = RedemptionConfig { payment_token: payment_token.clone(), fee_bps, is_open };
+        env.storage().persistent().set(&RWAKey::RedemptionConfig(asset_id), &config);
+        
+        env.events().publish(
+            (Symbol::new(&env, "RedemptionWindowUpdated"),),
+            (asset_id, payment_token, fee_bps, is_open),
+        );
+    }
+    
+    /// Issuer deposits payment tokens to pay redemptions with
+    pub fn fund_redemptions(env: Env, asset_id: u64, amount: i128) {
+        let asset: RWAAsset = env.storage().persistent()
+            .get(&RWAKey::Asset(asset_id))
+            .expect("Asset not found");
+        require_issuer(&env, &asset);
+        
+        if amount <= 0 {
+            panic!("Invalid amount");
+        }
+        
+        let config: RedemptionConfig = env.storage().persistent()
+            .get(&RWAKey::RedemptionConfig(asset_id))
+            .expect("Redemptions not configured");
+        
+        let client = token::Client::new(&env, &config.payment_token);
+        client.transfer(&asset.issuer, &env.current_contract_address(), &amount);
+        
+        let liquidity: i128 = env.storage().persistent()
+            .get(&RWAKey::RedemptionLiquidity(asset_id))
+            .unwrap_or(0);
+        env.storage().persistent().set(&RWAKey::RedemptionLiquidity(asset_id), &(liquidity + amount));
+        
+        env.events().publish(
+            (Symbol::new(&env, "RedemptionFunded"),),
+            (asset_id, amount),
+        );
+    }
+    
+    /// Issuer takes back unused redemption liquidity
+    pub fn withdraw_redemption_liquidity(env: Env, asset_id: u64, amount: i128) {
+        let asset: RWAAsset = env.storage().persistent()
+            .get(&RWAKey::Asset(asset_id))
+            .expect("Asset not found");
+        require_issuer(&env, &asset);
+        
+        let liquidity: i128 = env.storage().persistent()
+            .get(&RWAKey::RedemptionLiquidity(asset_id))
+            .unwrap_or(0);
+        if amount <= 0 || amount > liquidity {
+            panic!("Invalid amount");
+        }
+        
+        let config: RedemptionConfig = env.storage().persistent()
+            .get(&RWAKey::RedemptionConfig(asset_id))
+            .expect("Redemptions not configured");
+        
+        let client = token::Client::new(&env, &config.payment_token);
+        client.transfer(&env.current_contract_address(), &asset.issuer, &amount);
+        
+        env.storage().persistent().set(&RWAKey::RedemptionLiquidity(asset_id), &(liquidity - amount));
+    }
+    
+    /// Queue tokens for redemption. The tokens move into the contract's
+    /// custody until the request is settled or cancelled.
+    pub fn request_redemption(env: Env, asset_id: u64, investor_address: Address, amount: i128) -> u64 {
+        investor_address.require_auth();
+        
+        if amount <= 0 {
+            panic!("Invalid amount");
+        }
+        
+        let config: RedemptionConfig = env.storage().persistent()
+            .get(&RWAKey::RedemptionConfig(asset_id))
+            .expect("Redemptions not configured");
+        if !config.is_open {
+            panic!("Redemption window closed");
+        }
+        
+        let is_blacklisted: bool = env.storage().persistent()
+            .get(&RWAKey::BlacklistedAddress(investor_address.clone()))
+            .unwrap_or(false);
+        if is_blacklisted {
+            panic!("Address is blacklisted");
+        }
+        
+        let mut holding: Holding = match env.storage().persistent().get(&RWAKey::Holding(asset_id, investor_address.clone())) {
             Some(h) => h,
             None => panic!("No holding found"),
         };
         
-        // Holding must have been acquired before snapshot
-        if holding.acquired_at > distribution.snapshot_time {
-            panic!("Holding acquired after snapshot");
+        if holding.amount < amount {
+            panic!("Insufficient balance");
         }
         
-        // Calculate claim amount
-        let claim_amount = holding.amount * distribution.per_token_amount;
+        if spendable_balance(&env, asset_id, &investor_address, holding.amount) < amount {
+            panic!("Tokens are locked or frozen");
+        }
         
-        if claim_amount <= 0 {
-            panic!("Nothing to claim");
+        let asset_token: Address = env.storage().persistent()
+            .get(&RWAKey::AssetToken(asset_id))
+            .expect("Asset token not found");
+        let asset_client = token::Client::new(&env, &asset_token);
+        asset_client.transfer(&investor_address, &env.current_contract_address(), &amount);
+        
+        settle_rewards(&env, asset_id, &investor_address, holding.amount);
+        holding.amount -= amount;
+        env.storage().persistent().set(&RWAKey::Holding(asset_id, investor_address.clone()), &holding);
+        write_checkpoint(&env, asset_id, &investor_address, holding.amount);
+        track_holder(&env, asset_id, &investor_address, holding.amount + amount, holding.amount);
+        
+        let request_id: u64 = env.storage().persistent()
+            .get(&RWAKey::RedemptionCounter)
+            .unwrap_or(0) + 1;
+        
+        let request = RedemptionRequest {
+            request_id,
+            asset_id,
+            investor: investor_address.clone(),
+            amount,
+            requested_at: env.ledger().timestamp(),
+            payout: 0,
+            status: RedemptionStatus::Pending,
+        };
+        env.storage().persistent().set(&RWAKey::Redemption(request_id), &request);
+        env.storage().persistent().set(&RWAKey::RedemptionCounter, &request_id);
+        
+        let mut queue: Vec<u64> = env.storage().persistent()
+            .get(&RWAKey::RedemptionQueue(asset_id))
+            .unwrap_or(Vec::new(&env));
+        queue.push_back(request_id);
+        env.storage().persistent().set(&RWAKey::RedemptionQueue(asset_id), &queue);
+        
+        env.events().publish(
+            (Symbol::new(&env, "RedemptionRequested"),),
+            (request_id, asset_id, investor_address, amount),
+        );
+        
+        request_id
+    }
+    
+    /// Withdraw a pending request and take the tokens back
+    pub fn cancel_redemption(env: Env, request_id: u64) {
+        let mut request: RedemptionRequest = env.storage().persistent()
+            .get(&RWAKey::Redemption(request_id))
+            .expect("Redemption not found");
+        request.investor.require_auth();
+        
+        if request.status != RedemptionStatus::Pending {
+            panic!("Redemption not pending");
         }
         
-        // Transfer distribution tokens
-        let client = token::Client::new(&env, &distribution.distribution_token);
-        client.transfer(&env.current_contract_address(), &investor_address, &claim_amount);
+        let asset_id = request.asset_id;
+        let mut queue: Vec<u64> = env.storage().persistent()
+            .get(&RWAKey::RedemptionQueue(asset_id))
+            .unwrap_or(Vec::new(&env));
+        if let Some(position) = queue.first_index_of(request_id) {
+            queue.remove(position);
+        }
+        env.storage().persistent().set(&RWAKey::RedemptionQueue(asset_id), &queue);
+        
+        let mut holding: Holding = env.storage().persistent()
+            .get(&RWAKey::Holding(asset_id, request.investor.clone()))
+            .expect("No holding found");
+        
+        // Getting the tokens back makes a fully redeemed investor a holder
+        // again, so the holder caps apply as they would to a new investor
+        if holding.amount == 0 {
+            let investor: Investor = env.storage().persistent()
+                .get(&RWAKey::Investor(request.investor.clone()))
+                .expect("Investor not registered");
+            require_jurisdiction(&env, asset_id, &investor);
+        }
+        
+        let asset_token: Address = env.storage().persistent()
+            .get(&RWAKey::AssetToken(asset_id))
+            .expect("Asset token not found");
+        let asset_client = token::Client::new(&env, &asset_token);
+        asset_client.transfer(&env.current_contract_address(), &request.investor, &request.amount);
+        
+        settle_rewards(&env, asset_id, &request.investor, holding.amount);
+        holding.amount += request.amount;
+        env.storage().persistent().set(&RWAKey::Holding(asset_id, request.investor.clone()), &holding);
+        write_checkpoint(&env, asset_id, &request.investor, holding.amount);
+        track_holder(&env, asset_id, &request.investor, holding.amount - request.amount, holding.amount);
         
-        // Mark as claimed
-        env.storage().persistent().set(&RWAKey::ClaimedDistribution(distribution_id, investor_address.clone()), &true);
+        request.status = RedemptionStatus::Cancelled;
+        env.storage().persistent().set(&RWAKey::Redemption(request_id), &request);
         
         env.events().publish(
-            (Symbol::new(&env, "DistributionClaimed"),),
-            (distribution_id, investor_address, claim_amount),
+            (Symbol::new(&env, "RedemptionCancelled"),),
+            (request_id, asset_id, request.investor),
         );
+    }
+    
+    /// Pay queued requests in order at NAV less the redemption fee, up to
+    /// `max_requests`. Stops at the first request the funded liquidity
+    /// cannot cover, so later requests never jump the queue. Returns the
+    /// number of requests settled.
+    pub fn settle_redemptions(env: Env, asset_id: u64, max_requests: u32) -> u32 {
+        let mut asset: RWAAsset = env.storage().persistent()
+            .get(&RWAKey::Asset(asset_id))
+            .expect("Asset not found");
+        require_issuer(&env, &asset);
+        
+        let config: RedemptionConfig = env.storage().persistent()
+            .get(&RWAKey::RedemptionConfig(asset_id))
+            .expect("Redemptions not configured");
+        let mut liquidity: i128 = env.storage().persistent()
+            .get(&RWAKey::RedemptionLiquidity(asset_id))
+            .unwrap_or(0);
+        let mut queue: Vec<u64> = env.storage().persistent()
+            .get(&RWAKey::RedemptionQueue(asset_id))
+            .unwrap_or(Vec::new(&env));
         
-        claim_amount
+        let asset_token: Address = env.storage().persistent()
+            .get(&RWAKey::AssetToken(asset_id))
+            .expect("Asset token not found");
+        let asset_client = token::Client::new(&env, &asset_token);
+        let payment_client = token::Client::new(&env, &config.payment_token);
+        
+        let mut settled = 0;
+        while settled < max_requests {
+            let request_id = match queue.first() {
+                Some(id) => id,
+                None => break,
+            };
+            let mut request: RedemptionRequest = env.storage().persistent()
+                .get(&RWAKey::Redemption(request_id))
+                .expect("Redemption not found");
+            
+            // NAV per token is asset_value_usd / total_supply
+            let gross = request.amount * asset.asset_value_usd / asset.total_supply;
+            let fee = gross * config.fee_bps as i128 / BPS_DENOMINATOR;
+            let payout = gross - fee;
+            if payout > liquidity {
+                break;
+            }
+            
+            payment_client.transfer(&env.current_contract_address(), &request.investor, &payout);
+            asset_client.transfer(&env.current_contract_address(), &asset.issuer, &request.amount);
+            
+            liquidity -= payout;
+            asset.circulating_supply -= request.amount;
+            queue.pop_front();
+            
+            request.payout = payout;
+            request.status = RedemptionStatus::Settled;
+            env.storage().persistent().set(&RWAKey::Redemption(request_id), &request);
+            
+            env.events().publish(
+                (Symbol::new(&env, "RedemptionSettled"),),
+                (request_id, asset_id, request.investor, request.amount, payout, fee),
+            );
+            
+            settled += 1;
+        }
+        
+        env.storage().persistent().set(&RWAKey::Asset(asset_id), &asset);
+        env.storage().persistent().set(&RWAKey::RedemptionLiquidity(asset_id), &liquidity);
+        env.storage().persistent().set(&RWAKey::RedemptionQueue(asset_id), &queue);
+        
+        settled
+    }
+    
+    /// Get a redemption request
+    pub fn get_redemption(env: Env, request_id: u64) -> Option<RedemptionRequest> {
+        env.storage().persistent().get(&RWAKey::Redemption(request_id))
+    }
+    
+    /// Get pending redemption request ids for an asset, oldest first
+    pub fn get_redemption_queue(env: Env, asset_id: u64) -> Vec<u64> {
+        env.storage().persistent()
+            .get(&RWAKey::RedemptionQueue(asset_id))
+            .unwrap_or(Vec::new(&env))
+    }
+    
+    /// Get an asset's redemption window settings
+    pub fn get_redemption_config(env: Env, asset_id: u64) -> Option<RedemptionConfig> {
+        env.storage().persistent().get(&RWAKey::RedemptionConfig(asset_id))
+    }
+    
+    /// Get unused redemption liquidity for an asset
+    pub fn get_redemption_liquidity(env: Env, asset_id: u64) -> i128 {
+        env.storage().persistent()
+            .get(&RWAKey::RedemptionLiquidity(asset_id))
+            .unwrap_or(0)
     }
     
     // ============== View Functions ==============
@@ -691,6 +2485,88 @@ impl RWAContract {
         env.storage().persistent().get(&RWAKey::Asset(asset_id))
     }
     
+    /// Get an asset's valuation rules
+    pub fn get_valuation_config(env: Env, asset_id: u64) -> ValuationConfig {
+        valuation_config(&env, asset_id)
+    }
+    
+    /// Get appraisals still waiting for a quorum
+    pub fn get_pending_valuations(env: Env, asset_id: u64) -> Vec<ValuationSubmission> {
+        env.storage().persistent()
+            .get(&RWAKey::ValuationRound(asset_id))
+            .unwrap_or(Vec::new(&env))
+    }
+    
+    /// Get every accepted valuation of an asset, oldest first
+    pub fn get_valuation_history(env: Env, asset_id: u64) -> Vec<ValuationRecord> {
+        env.storage().persistent()
+            .get(&RWAKey::ValuationHistory(asset_id))
+            .unwrap_or(Vec::new(&env))
+    }
+    
+    /// Get the valuation in force at a ledger timestamp
+    pub fn get_valuation_at(env: Env, asset_id: u64, timestamp: u64) -> Option<ValuationRecord> {
+        let history: Vec<ValuationRecord> = env.storage().persistent()
+            .get(&RWAKey::ValuationHistory(asset_id))
+            .unwrap_or(Vec::new(&env));
+        
+        // Binary search for the first record after `timestamp`
+        let mut low = 0;
+        let mut high = history.len();
+        while low < high {
+            let mid = (low + high) / 2;
+            if history.get_unchecked(mid).timestamp <= timestamp {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        
+        if low == 0 {
+            None
+        } else {
+            Some(history.get_unchecked(low - 1))
+        }
+    }
+    
+    /// Get a document of an asset by name
+    pub fn get_document(env: Env, asset_id: u64, name: String) -> Option<Document> {
+        env.storage().persistent().get(&RWAKey::Document(asset_id, name))
+    }
+    
+    /// Get all documents of an asset in the order they were added
+    pub fn get_documents(env: Env, asset_id: u64) -> Vec<Document> {
+        let names: Vec<String> = env.storage().persistent()
+            .get(&RWAKey::DocumentNames(asset_id))
+            .unwrap_or(Vec::new(&env));
+        
+        let mut documents = Vec::new(&env);
+        for name in names.iter() {
+            if let Some(document) = env.storage().persistent().get(&RWAKey::Document(asset_id, name)) {
+                documents.push_back(document);
+            }
+        }
+        
+        documents
+    }
+    
+    /// Check whether an investor acknowledged the current version of a document
+    pub fn has_acknowledged(env: Env, asset_id: u64, name: String, investor_address: Address) -> bool {
+        let document: Document = match env.storage().persistent().get(&RWAKey::Document(asset_id, name.clone())) {
+            Some(d) => d,
+            None => return false,
+        };
+        
+        let acknowledged: Option<BytesN<32>> = env.storage().persistent()
+            .get(&RWAKey::Acknowledgement(asset_id, name, investor_address));
+        acknowledged == Some(document.content_hash)
+    }
+    
+    /// Check whether an account holds a role
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        holds_role(&env, role, &account)
+    }
+    
     /// Get investor details
     pub fn get_investor(env: Env, investor_address: Address) -> Option<Investor> {
         env.storage().persistent().get(&RWAKey::Investor(investor_address))
@@ -701,16 +2577,89 @@ impl RWAContract {
         env.storage().persistent().get(&RWAKey::Holding(asset_id, investor_address))
     }
     
+    /// Get an investor's lock-up tranches in an asset
+    pub fn get_lockups(env: Env, asset_id: u64, investor_address: Address) -> Vec<LockupTranche> {
+        env.storage().persistent()
+            .get(&RWAKey::Lockups(asset_id, investor_address))
+            .unwrap_or(Vec::new(&env))
+    }
+    
+    /// Get the part of a holding that is neither locked up nor frozen
+    pub fn get_transferable_balance(env: Env, asset_id: u64, investor_address: Address) -> i128 {
+        let balance = env.storage().persistent()
+            .get::<_, Holding>(&RWAKey::Holding(asset_id, investor_address.clone()))
+            .map(|h| h.amount)
+            .unwrap_or(0);
+        spendable_balance(&env, asset_id, &investor_address, balance)
+    }
+    
+    /// Get the frozen part of an investor's holding
+    pub fn get_frozen_balance(env: Env, asset_id: u64, investor_address: Address) -> i128 {
+        frozen_balance(&env, asset_id, &investor_address)
+    }
+    
+    /// Get an investor's balance of an asset as of a ledger timestamp.
+    /// Served from balance checkpoints; claims do not use it
+    pub fn balance_of_at(env: Env, asset_id: u64, investor_address: Address, timestamp: u64) -> i128 {
+        balance_at(&env, asset_id, &investor_address, timestamp)
+    }
+    
     /// Get distribution details
     pub fn get_distribution(env: Env, distribution_id: u64) -> Option<Distribution> {
         env.storage().persistent().get(&RWAKey::Distribution(distribution_id))
     }
     
+    /// Get claimed and remaining totals of a distribution
+    pub fn get_distribution_status(env: Env, distribution_id: u64) -> Option<DistributionStatus> {
+        let distribution: Distribution = env.storage().persistent().get(&RWAKey::Distribution(distribution_id))?;
+        
+        Some(DistributionStatus {
+            distribution_id,
+            total_amount: distribution.total_amount,
+            claimed_amount: distribution.claimed / REWARD_SCALE,
+            claimant_count: distribution.claimant_count,
+            remaining: if distribution.is_reclaimed { 0 } else { distribution_remaining(&distribution) },
+            claim_deadline: distribution.claim_deadline,
+            is_reclaimed: distribution.is_reclaimed,
+        })
+    }
+    
     /// Check if distribution is claimed
     pub fn is_distribution_claimed(env: Env, distribution_id: u64, investor_address: Address) -> bool {
+        let distribution: Distribution = match env.storage().persistent().get(&RWAKey::Distribution(distribution_id)) {
+            Some(d) => d,
+            None => return false,
+        };
+        
+        let state: Option<RewardState> = env.storage().persistent()
+            .get(&RWAKey::Reward(distribution.asset_id, distribution.distribution_token, investor_address));
+        match state {
+            Some(s) => s.claimed_index >= distribution.reward_index,
+            None => false,
+        }
+    }
+    
+    /// Get the rewards an investor could claim now in one distribution token
+    pub fn get_claimable(env: Env, asset_id: u64, token: Address, investor_address: Address) -> i128 {
+        let state: RewardState = env.storage().persistent()
+            .get(&RWAKey::Reward(asset_id, token.clone(), investor_address.clone()))
+            .unwrap_or(RewardState { index: 0, accrued: 0, claimed_index: 0 });
+        let balance = env.storage().persistent()
+            .get::<_, Holding>(&RWAKey::Holding(asset_id, investor_address.clone()))
+            .map(|h| h.amount)
+            .unwrap_or(0);
+        
+        let pending = accrue_distributions(&env, asset_id, &token, &investor_address, state.index, balance, false);
+        let expired = collect_credits(&env, asset_id, &token, &investor_address, state.claimed_index, false);
+        (state.accrued + pending - expired) / REWARD_SCALE
+    }
+    
+    /// Get the undistributed remainder carried into the next distribution,
+    /// in REWARD_SCALE units (always less than the circulating supply)
+    pub fn get_reward_dust(env: Env, asset_id: u64, token: Address) -> i128 {
         env.storage().persistent()
-            .get(&RWAKey::ClaimedDistribution(distribution_id, investor_address))
-            .unwrap_or(false)
+            .get(&RWAKey::RewardDust(asset_id, token))
+            .unwrap_or(0)
     }
     
     /// Get total value locked
@@ -727,43 +2676,63 @@ impl RWAContract {
             .unwrap_or(0)
     }
     
-    /// Check if investor is eligible for an asset
-    pub fn check_eligibility(env: Env, asset_id: u64, investor_address: Address) -> bool {
+    /// Check if investor is eligible for an asset, naming the first rule that fails
+    pub fn check_eligibility(env: Env, asset_id: u64, investor_address: Address) -> Eligibility {
         // Check blacklist
         let is_blacklisted: bool = env.storage().persistent()
             .get(&RWAKey::BlacklistedAddress(investor_address.clone()))
             .unwrap_or(false);
         if is_blacklisted {
-            return false;
+            return Eligibility::Blacklisted;
         }
         
         // Get asset
         let asset: RWAAsset = match env.storage().persistent().get(&RWAKey::Asset(asset_id)) {
             Some(a) => a,
-            None => return false,
+            None => return Eligibility::AssetNotFound,
         };
         
         if !asset.is_active {
-            return false;
+            return Eligibility::AssetInactive;
         }
         
         // Get investor
         let investor: Investor = match env.storage().persistent().get(&RWAKey::Investor(investor_address)) {
             Some(i) => i,
-            None => return false,
+            None => return Eligibility::NotRegistered,
         };
         
         // Check KYC
         if !investor.is_kyc_verified || investor.kyc_expiry < env.ledger().timestamp() {
-            return false;
+            return Eligibility::KycExpired;
         }
         
         // Check accreditation
         if asset.accredited_only && !investor.is_accredited {
-            return false;
+            return Eligibility::NotAccredited;
         }
         
-        true
+        // Check per-asset country rules and holder caps
+        jurisdiction_rule(&env, asset_id, &investor)
+    }
+    
+    /// Get an asset's jurisdiction rules
+    pub fn get_jurisdiction_rules(env: Env, asset_id: u64) -> Option<JurisdictionRules> {
+        env.storage().persistent().get(&RWAKey::JurisdictionRules(asset_id))
+    }
+    
+    /// Get the number of investors holding an asset
+    pub fn get_holder_count(env: Env, asset_id: u64) -> u32 {
+        env.storage().persistent()
+            .get(&RWAKey::HolderCount(asset_id))
+            .unwrap_or(0)
+    }
+    
+    /// Get the number of investors from a country holding an asset
+    pub fn get_country_holder_count(env: Env, asset_id: u64, country_code: String) -> u32 {
+        env.storage().persistent()
+            .get(&RWAKey::CountryHolderCount(asset_id, country_code))
+            .unwrap_or(0)
     }
     
     /// Calculate price per token for an asset
@@ -5,11 +5,11 @@
 
 #[cfg(test)]
 mod rwa_tests {
-    use crate::rwa::{RWAContract, RWAContractClient, AssetType, RWAAsset, Investor, Holding};
+    use crate::rwa::{RWAContract, RWAContractClient, AssetType, ComplianceTerms, RWAAsset, Investor, Holding, RedemptionStatus, Eligibility, Role};
     use soroban_sdk::{
-        testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Ledger, LedgerInfo},
+        testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, IssuerFlags, Ledger, LedgerInfo},
         token::{self, Client as TokenClient, StellarAssetClient},
-        Address, Env, IntoVal, String, Symbol,
+        vec, Address, Bytes, BytesN, Env, IntoVal, String, Symbol, Vec,
     };
 
     // ============== Helper Functions ==============
@@ -53,8 +53,11 @@ mod rwa_tests {
 
         client.initialize(&admin);
 
+        client.grant_role(&admin, &Role::Custodian, &custodian);
+
         // Create an asset
         let asset_id = client.create_asset(
+            &admin,
             &String::from_str(&env, "Test Property"),
             &String::from_str(&env, "TPROP"),
             &AssetType::RealEstate,
@@ -62,13 +65,74 @@ mod rwa_tests {
             &100000000,    // $1M USD (in cents)
             &custodian,
             &token_address,
-            &100,          // min 100 tokens
-            &false,        // not accredited only
+            &ComplianceTerms { min_investment: 100, accredited_only: false },
         );
 
         (env, admin, custodian, token_address, client, asset_id)
     }
 
+    /// Asset with two registered investors holding payment tokens, plus a
+    /// distribution token held by the admin. `invest` moves asset tokens out
+    /// of the issuer's account, which needs non-root auth mocking.
+    fn setup_market() -> (
+        Env,
+        Address,
+        Address,
+        Address,
+        Address,
+        Address,
+        RWAContractClient<'static>,
+        u64,
+    ) {
+        let env = Env::default();
+        env.mock_all_auths_allowing_non_root_auth();
+        env.ledger().with_mut(|li| li.timestamp = 1000);
+
+        let admin = Address::generate(&env);
+        let custodian = Address::generate(&env);
+        let investor1 = Address::generate(&env);
+        let investor2 = Address::generate(&env);
+
+        let (payment_token, _, payment_admin) = create_token_contract(&env, &admin);
+        payment_admin.mint(&investor1, &1000000);
+        payment_admin.mint(&investor2, &1000000);
+
+        // Clawback lets compliance agents move tokens without the holder
+        let asset_contract = env.register_stellar_asset_contract_v2(admin.clone());
+        asset_contract.issuer().set_flag(IssuerFlags::RevocableFlag);
+        asset_contract.issuer().set_flag(IssuerFlags::ClawbackEnabledFlag);
+        let asset_token = asset_contract.address();
+        StellarAssetClient::new(&env, &asset_token).mint(&admin, &100000);
+
+        let (dist_token, _, dist_admin) = create_token_contract(&env, &admin);
+        dist_admin.mint(&admin, &1000000);
+
+        let contract_id = env.register_contract(None, RWAContract);
+        let client = RWAContractClient::new(&env, &contract_id);
+        client.initialize(&admin);
+
+        let country_code = String::from_str(&env, "US");
+        client.whitelist_country(&admin, &country_code, &true);
+        let kyc_expiry = env.ledger().timestamp() + 365 * 86400;
+        client.register_investor(&admin, &investor1, &false, &country_code, &kyc_expiry);
+        client.register_investor(&admin, &investor2, &false, &country_code, &kyc_expiry);
+
+        client.grant_role(&admin, &Role::Custodian, &custodian);
+        let asset_id = client.create_asset(
+            &admin,
+            &String::from_str(&env, "Market Asset"),
+            &String::from_str(&env, "MKT"),
+            &AssetType::RealEstate,
+            &100000,
+            &10000000,
+            &custodian,
+            &asset_token,
+            &ComplianceTerms { min_investment: 100, accredited_only: false },
+        );
+
+        (env, admin, investor1, investor2, payment_token, dist_token, client, asset_id)
+    }
+
     // ============== Initialization Tests ==============
 
     #[test]
@@ -118,7 +182,7 @@ mod rwa_tests {
         let (env, admin, client) = setup_test_env();
 
         let country_code = String::from_str(&env, "US");
-        client.whitelist_country(&country_code, &true);
+        client.whitelist_country(&admin, &country_code, &true);
 
         // Create investor to verify whitelist works
         let investor = Address::generate(&env);
@@ -126,6 +190,7 @@ mod rwa_tests {
 
         // This should succeed with whitelisted country
         let result = client.register_investor(
+            &admin,
             &investor,
             &true,
             &country_code,
@@ -143,7 +208,7 @@ mod rwa_tests {
         let country_code = String::from_str(&env, "XX"); // Not whitelisted
         let kyc_expiry = env.ledger().timestamp() + 365 * 86400;
 
-        client.register_investor(&investor, &true, &country_code, &kyc_expiry);
+        client.register_investor(&admin, &investor, &true, &country_code, &kyc_expiry);
     }
 
     #[test]
@@ -151,7 +216,7 @@ mod rwa_tests {
         let (env, admin, client) = setup_test_env();
 
         let blacklisted_address = Address::generate(&env);
-        client.blacklist_address(&blacklisted_address, &true);
+        client.blacklist_address(&admin, &blacklisted_address, &true);
 
         // Verify the address is blacklisted through eligibility check
     }
@@ -171,7 +236,9 @@ mod rwa_tests {
         let client = RWAContractClient::new(&env, &contract_id);
         client.initialize(&admin);
 
+        client.grant_role(&admin, &Role::Custodian, &custodian);
         let asset_id = client.create_asset(
+            &admin,
             &String::from_str(&env, "Manhattan Tower"),
             &String::from_str(&env, "MTWR"),
             &AssetType::RealEstate,
@@ -179,8 +246,7 @@ mod rwa_tests {
             &50000000000, // $500M
             &custodian,
             &token_address,
-            &1000,
-            &true, // accredited only
+            &ComplianceTerms { min_investment: 1000, accredited_only: true },
         );
 
         assert_eq!(asset_id, 1);
@@ -206,7 +272,9 @@ mod rwa_tests {
         let client = RWAContractClient::new(&env, &contract_id);
         client.initialize(&admin);
 
+        client.grant_role(&admin, &Role::Custodian, &custodian);
         let id1 = client.create_asset(
+            &admin,
             &String::from_str(&env, "Asset 1"),
             &String::from_str(&env, "AST1"),
             &AssetType::RealEstate,
@@ -214,11 +282,11 @@ mod rwa_tests {
             &100000000,
             &custodian,
             &token_address,
-            &100,
-            &false,
+            &ComplianceTerms { min_investment: 100, accredited_only: false },
         );
 
         let id2 = client.create_asset(
+            &admin,
             &String::from_str(&env, "Asset 2"),
             &String::from_str(&env, "AST2"),
             &AssetType::Commodity,
@@ -226,11 +294,11 @@ mod rwa_tests {
             &50000000,
             &custodian,
             &token_address,
-            &50,
-            &false,
+            &ComplianceTerms { min_investment: 50, accredited_only: false },
         );
 
         let id3 = client.create_asset(
+            &admin,
             &String::from_str(&env, "Asset 3"),
             &String::from_str(&env, "AST3"),
             &AssetType::Bond,
@@ -238,8 +306,7 @@ mod rwa_tests {
             &200000000,
             &custodian,
             &token_address,
-            &1000,
-            &true,
+            &ComplianceTerms { min_investment: 1000, accredited_only: true },
         );
 
         assert_eq!(id1, 1);
@@ -256,7 +323,9 @@ mod rwa_tests {
         let custodian = Address::generate(&env);
         let (token_address, _, _) = create_token_contract(&env, &admin);
 
+        client.grant_role(&admin, &Role::Custodian, &custodian);
         client.create_asset(
+            &admin,
             &String::from_str(&env, "Bad Asset"),
             &String::from_str(&env, "BAD"),
             &AssetType::Other,
@@ -264,22 +333,25 @@ mod rwa_tests {
             &100000000,
             &custodian,
             &token_address,
-            &100,
-            &false,
+            &ComplianceTerms { min_investment: 100, accredited_only: false },
         );
     }
 
     // ============== Valuation Tests ==============
 
     #[test]
-    fn test_update_valuation() {
+    fn test_submit_valuation() {
         let (env, admin, custodian, token_address, client, asset_id) = setup_with_asset();
 
         let old_asset = client.get_asset(&asset_id).unwrap();
         assert_eq!(old_asset.asset_value_usd, 100000000);
 
+        // Appraisals are refused until the asset has valuation rules
+        assert!(client.try_submit_valuation(&admin, &asset_id, &150000000).is_err());
+        client.set_valuation_config(&admin, &asset_id, &1, &0, &10000, &0);
+
         // Update valuation to $1.5M
-        let result = client.update_valuation(&asset_id, &150000000);
+        let result = client.submit_valuation(&admin, &asset_id, &150000000);
         assert!(result);
 
         let new_asset = client.get_asset(&asset_id).unwrap();
@@ -287,10 +359,10 @@ mod rwa_tests {
     }
 
     #[test]
-    fn test_update_valuation_nonexistent() {
+    fn test_submit_valuation_nonexistent() {
         let (env, admin, client) = setup_test_env();
 
-        let result = client.update_valuation(&999, &100000000);
+        let result = client.submit_valuation(&admin, &999, &100000000);
         assert!(!result);
     }
 
@@ -305,12 +377,12 @@ mod rwa_tests {
         assert!(asset.is_transferable);
 
         // Disable transfers
-        client.set_asset_transferable(&asset_id, &false);
+        client.set_asset_transferable(&admin, &asset_id, &false);
         let asset = client.get_asset(&asset_id).unwrap();
         assert!(!asset.is_transferable);
 
         // Re-enable transfers
-        client.set_asset_transferable(&asset_id, &true);
+        client.set_asset_transferable(&admin, &asset_id, &true);
         let asset = client.get_asset(&asset_id).unwrap();
         assert!(asset.is_transferable);
     }
@@ -322,12 +394,13 @@ mod rwa_tests {
         let (env, admin, client) = setup_test_env();
 
         let country_code = String::from_str(&env, "US");
-        client.whitelist_country(&country_code, &true);
+        client.whitelist_country(&admin, &country_code, &true);
 
         let investor = Address::generate(&env);
         let kyc_expiry = env.ledger().timestamp() + 365 * 86400;
 
         let result = client.register_investor(
+            &admin,
             &investor,
             &true,  // accredited
             &country_code,
@@ -345,18 +418,18 @@ mod rwa_tests {
         let (env, admin, client) = setup_test_env();
 
         let country_code = String::from_str(&env, "US");
-        client.whitelist_country(&country_code, &true);
+        client.whitelist_country(&admin, &country_code, &true);
 
         let investor = Address::generate(&env);
         let kyc_expiry = env.ledger().timestamp() + 365 * 86400;
 
-        client.register_investor(&investor, &false, &country_code, &kyc_expiry);
+        client.register_investor(&admin, &investor, &false, &country_code, &kyc_expiry);
 
         let investor_data = client.get_investor(&investor).unwrap();
         assert!(!investor_data.is_accredited);
 
         // Update accreditation
-        client.update_accreditation(&investor, &true);
+        client.update_accreditation(&admin, &investor, &true);
         let investor_data = client.get_investor(&investor).unwrap();
         assert!(investor_data.is_accredited);
     }
@@ -386,16 +459,20 @@ mod rwa_tests {
 
         // Whitelist country and register investor
         let country_code = String::from_str(&env, "US");
-        client.whitelist_country(&country_code, &true);
+        client.whitelist_country(&admin, &country_code, &true);
         client.register_investor(
+            &admin,
             &investor,
             &false,
             &country_code,
             &(env.ledger().timestamp() + 365 * 86400),
         );
 
+        client.grant_role(&admin, &Role::Custodian, &custodian);
+
         // Create asset
         let asset_id = client.create_asset(
+            &admin,
             &String::from_str(&env, "Test Asset"),
             &String::from_str(&env, "TEST"),
             &AssetType::RealEstate,
@@ -403,8 +480,7 @@ mod rwa_tests {
             &100000000,
             &custodian,
             &asset_token,
-            &100,
-            &false,
+            &ComplianceTerms { min_investment: 100, accredited_only: false },
         );
 
         // Invest
@@ -465,15 +541,18 @@ mod rwa_tests {
         client.initialize(&admin);
 
         let country_code = String::from_str(&env, "US");
-        client.whitelist_country(&country_code, &true);
+        client.whitelist_country(&admin, &country_code, &true);
         client.register_investor(
+            &admin,
             &investor,
             &false,
             &country_code,
             &(env.ledger().timestamp() + 365 * 86400),
         );
 
+        client.grant_role(&admin, &Role::Custodian, &custodian);
         let asset_id = client.create_asset(
+            &admin,
             &String::from_str(&env, "Test"),
             &String::from_str(&env, "TST"),
             &AssetType::RealEstate,
@@ -481,8 +560,7 @@ mod rwa_tests {
             &100000000,
             &custodian,
             &token_address,
-            &1000, // min 1000 tokens
-            &false,
+            &ComplianceTerms { min_investment: 1000, accredited_only: false },
         );
 
         // Try to invest below minimum
@@ -508,15 +586,18 @@ mod rwa_tests {
         client.initialize(&admin);
 
         let country_code = String::from_str(&env, "US");
-        client.whitelist_country(&country_code, &true);
+        client.whitelist_country(&admin, &country_code, &true);
         client.register_investor(
+            &admin,
             &investor,
             &false, // NOT accredited
             &country_code,
             &(env.ledger().timestamp() + 365 * 86400),
         );
 
+        client.grant_role(&admin, &Role::Custodian, &custodian);
         let asset_id = client.create_asset(
+            &admin,
             &String::from_str(&env, "Accredited Only Asset"),
             &String::from_str(&env, "ACC"),
             &AssetType::Security,
@@ -524,8 +605,7 @@ mod rwa_tests {
             &100000000,
             &custodian,
             &token_address,
-            &100,
-            &true, // accredited only
+            &ComplianceTerms { min_investment: 100, accredited_only: true },
         );
 
         client.invest(&asset_id, &investor, &1000, &payment_token, &100000);
@@ -554,14 +634,16 @@ mod rwa_tests {
         client.initialize(&admin);
 
         let country_code = String::from_str(&env, "US");
-        client.whitelist_country(&country_code, &true);
+        client.whitelist_country(&admin, &country_code, &true);
 
         // Register both investors
         let kyc_expiry = env.ledger().timestamp() + 365 * 86400;
-        client.register_investor(&investor1, &false, &country_code, &kyc_expiry);
-        client.register_investor(&investor2, &false, &country_code, &kyc_expiry);
+        client.register_investor(&admin, &investor1, &false, &country_code, &kyc_expiry);
+        client.register_investor(&admin, &investor2, &false, &country_code, &kyc_expiry);
 
+        client.grant_role(&admin, &Role::Custodian, &custodian);
         let asset_id = client.create_asset(
+            &admin,
             &String::from_str(&env, "Transferable Asset"),
             &String::from_str(&env, "TRFR"),
             &AssetType::RealEstate,
@@ -569,8 +651,7 @@ mod rwa_tests {
             &100000000,
             &custodian,
             &asset_token,
-            &100,
-            &false,
+            &ComplianceTerms { min_investment: 100, accredited_only: false },
         );
 
         // Investor1 invests
@@ -609,13 +690,15 @@ mod rwa_tests {
         client.initialize(&admin);
 
         let country_code = String::from_str(&env, "US");
-        client.whitelist_country(&country_code, &true);
+        client.whitelist_country(&admin, &country_code, &true);
 
         let kyc_expiry = env.ledger().timestamp() + 365 * 86400;
-        client.register_investor(&investor1, &false, &country_code, &kyc_expiry);
-        client.register_investor(&investor2, &false, &country_code, &kyc_expiry);
+        client.register_investor(&admin, &investor1, &false, &country_code, &kyc_expiry);
+        client.register_investor(&admin, &investor2, &false, &country_code, &kyc_expiry);
 
+        client.grant_role(&admin, &Role::Custodian, &custodian);
         let asset_id = client.create_asset(
+            &admin,
             &String::from_str(&env, "Non-Transferable"),
             &String::from_str(&env, "NOTR"),
             &AssetType::Security,
@@ -623,12 +706,11 @@ mod rwa_tests {
             &100000000,
             &custodian,
             &asset_token,
-            &100,
-            &false,
+            &ComplianceTerms { min_investment: 100, accredited_only: false },
         );
 
         // Disable transfers
-        client.set_asset_transferable(&asset_id, &false);
+        client.set_asset_transferable(&admin, &asset_id, &false);
 
         // Invest
         client.invest(&asset_id, &investor1, &5000, &payment_token, &500000);
@@ -663,15 +745,18 @@ mod rwa_tests {
         client.initialize(&admin);
 
         let country_code = String::from_str(&env, "US");
-        client.whitelist_country(&country_code, &true);
+        client.whitelist_country(&admin, &country_code, &true);
         client.register_investor(
+            &admin,
             &investor,
             &false,
             &country_code,
             &(env.ledger().timestamp() + 365 * 86400),
         );
 
+        client.grant_role(&admin, &Role::Custodian, &custodian);
         let asset_id = client.create_asset(
+            &admin,
             &String::from_str(&env, "Dividend Asset"),
             &String::from_str(&env, "DIV"),
             &AssetType::RealEstate,
@@ -679,8 +764,7 @@ mod rwa_tests {
             &100000000,
             &custodian,
             &asset_token,
-            &100,
-            &false,
+            &ComplianceTerms { min_investment: 100, accredited_only: false },
         );
 
         // Investor buys tokens
@@ -723,15 +807,18 @@ mod rwa_tests {
         client.initialize(&admin);
 
         let country_code = String::from_str(&env, "US");
-        client.whitelist_country(&country_code, &true);
+        client.whitelist_country(&admin, &country_code, &true);
         client.register_investor(
+            &admin,
             &investor,
             &false,
             &country_code,
             &(env.ledger().timestamp() + 365 * 86400),
         );
 
+        client.grant_role(&admin, &Role::Custodian, &custodian);
         let asset_id = client.create_asset(
+            &admin,
             &String::from_str(&env, "Dividend Asset"),
             &String::from_str(&env, "DIV"),
             &AssetType::RealEstate,
@@ -739,8 +826,7 @@ mod rwa_tests {
             &10000000,
             &custodian,
             &asset_token,
-            &100,
-            &false,
+            &ComplianceTerms { min_investment: 100, accredited_only: false },
         );
 
         // Investor buys 10% of tokens
@@ -783,15 +869,18 @@ mod rwa_tests {
         client.initialize(&admin);
 
         let country_code = String::from_str(&env, "US");
-        client.whitelist_country(&country_code, &true);
+        client.whitelist_country(&admin, &country_code, &true);
         client.register_investor(
+            &admin,
             &investor,
             &false,
             &country_code,
             &(env.ledger().timestamp() + 365 * 86400),
         );
 
+        client.grant_role(&admin, &Role::Custodian, &custodian);
         let asset_id = client.create_asset(
+            &admin,
             &String::from_str(&env, "Test"),
             &String::from_str(&env, "TST"),
             &AssetType::RealEstate,
@@ -799,8 +888,7 @@ mod rwa_tests {
             &10000000,
             &custodian,
             &asset_token,
-            &100,
-            &false,
+            &ComplianceTerms { min_investment: 100, accredited_only: false },
         );
 
         client.invest(&asset_id, &investor, &10000, &payment_token, &1000000);
@@ -841,10 +929,11 @@ mod rwa_tests {
         let (env, admin, client) = setup_test_env();
 
         let country_code = String::from_str(&env, "US");
-        client.whitelist_country(&country_code, &true);
+        client.whitelist_country(&admin, &country_code, &true);
 
         let investor = Address::generate(&env);
         client.register_investor(
+            &admin,
             &investor,
             &true,
             &country_code,
@@ -872,15 +961,18 @@ mod rwa_tests {
         client.initialize(&admin);
 
         let country_code = String::from_str(&env, "US");
-        client.whitelist_country(&country_code, &true);
+        client.whitelist_country(&admin, &country_code, &true);
         client.register_investor(
+            &admin,
             &investor,
             &true,
             &country_code,
             &(env.ledger().timestamp() + 365 * 86400),
         );
 
+        client.grant_role(&admin, &Role::Custodian, &custodian);
         let asset_id = client.create_asset(
+            &admin,
             &String::from_str(&env, "Test"),
             &String::from_str(&env, "TST"),
             &AssetType::Security,
@@ -888,12 +980,11 @@ mod rwa_tests {
             &100000000,
             &custodian,
             &token_address,
-            &100,
-            &true, // accredited only
+            &ComplianceTerms { min_investment: 100, accredited_only: true },
         );
 
         let eligible = client.check_eligibility(&asset_id, &investor);
-        assert!(eligible);
+        assert_eq!(eligible, Eligibility::Eligible);
     }
 
     #[test]
@@ -921,7 +1012,9 @@ mod rwa_tests {
 
         assert_eq!(client.get_tvl(), 0);
 
+        client.grant_role(&admin, &Role::Custodian, &custodian);
         client.create_asset(
+            &admin,
             &String::from_str(&env, "Asset 1"),
             &String::from_str(&env, "A1"),
             &AssetType::RealEstate,
@@ -929,13 +1022,13 @@ mod rwa_tests {
             &100000000, // $1M
             &custodian,
             &token_address,
-            &100,
-            &false,
+            &ComplianceTerms { min_investment: 100, accredited_only: false },
         );
 
         assert_eq!(client.get_tvl(), 100000000);
 
         client.create_asset(
+            &admin,
             &String::from_str(&env, "Asset 2"),
             &String::from_str(&env, "A2"),
             &AssetType::Commodity,
@@ -943,8 +1036,7 @@ mod rwa_tests {
             &50000000, // $500K
             &custodian,
             &token_address,
-            &50,
-            &false,
+            &ComplianceTerms { min_investment: 50, accredited_only: false },
         );
 
         assert_eq!(client.get_tvl(), 150000000);
@@ -973,15 +1065,18 @@ mod rwa_tests {
         client.initialize(&admin);
 
         let country_code = String::from_str(&env, "US");
-        client.whitelist_country(&country_code, &true);
+        client.whitelist_country(&admin, &country_code, &true);
         client.register_investor(
+            &admin,
             &investor,
             &false,
             &country_code,
             &(env.ledger().timestamp() + 365 * 86400),
         );
 
+        client.grant_role(&admin, &Role::Custodian, &custodian);
         let asset_id = client.create_asset(
+            &admin,
             &String::from_str(&env, "Test"),
             &String::from_str(&env, "TST"),
             &AssetType::RealEstate,
@@ -989,8 +1084,7 @@ mod rwa_tests {
             &10000000,
             &custodian,
             &asset_token,
-            &100,
-            &false,
+            &ComplianceTerms { min_investment: 100, accredited_only: false },
         );
 
         client.invest(&asset_id, &investor, &10000, &payment_token, &1000000);
@@ -1006,4 +1100,841 @@ mod rwa_tests {
         // Now claimed
         assert!(client.is_distribution_claimed(&distribution_id, &investor));
     }
+
+    // ============== Balance Checkpoint Tests ==============
+
+    #[test]
+    fn test_balance_of_at() {
+        let (env, _admin, investor1, investor2, payment_token, _, client, asset_id) = setup_market();
+
+        client.invest(&asset_id, &investor1, &5000, &payment_token, &500000);
+        // A second change in the same ledger replaces the first checkpoint
+        client.invest(&asset_id, &investor1, &1000, &payment_token, &100000);
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        client.transfer(&asset_id, &investor1, &investor2, &2000);
+
+        assert_eq!(client.balance_of_at(&asset_id, &investor1, &999), 0);
+        assert_eq!(client.balance_of_at(&asset_id, &investor1, &1000), 6000);
+        assert_eq!(client.balance_of_at(&asset_id, &investor1, &1999), 6000);
+        assert_eq!(client.balance_of_at(&asset_id, &investor1, &2000), 4000);
+        assert_eq!(client.balance_of_at(&asset_id, &investor2, &1999), 0);
+        assert_eq!(client.balance_of_at(&asset_id, &investor2, &5000), 2000);
+    }
+
+    #[test]
+    fn test_claim_uses_snapshot_balance() {
+        let (env, _admin, investor1, investor2, payment_token, dist_token, client, asset_id) =
+            setup_market();
+
+        client.invest(&asset_id, &investor1, &10000, &payment_token, &100000);
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        let distribution_id = client.create_distribution(&asset_id, &100000, &dist_token);
+
+        // Topping up and buying in after the snapshot earns nothing extra
+        env.ledger().with_mut(|li| li.timestamp = 3000);
+        client.invest(&asset_id, &investor1, &10000, &payment_token, &100000);
+        client.invest(&asset_id, &investor2, &10000, &payment_token, &100000);
+
+        assert_eq!(client.claim_distribution(&distribution_id, &investor1), 100000);
+        assert_eq!(TokenClient::new(&env, &dist_token).balance(&investor1), 100000);
+        assert!(client.try_claim_distribution(&distribution_id, &investor2).is_err());
+    }
+
+    // ============== Reward Index Tests ==============
+
+    #[test]
+    fn test_distribution_smaller_than_supply_is_not_lost() {
+        let (env, _admin, investor1, investor2, payment_token, dist_token, client, asset_id) =
+            setup_market();
+        let dist_client = TokenClient::new(&env, &dist_token);
+
+        client.invest(&asset_id, &investor1, &100, &payment_token, &10000);
+        client.invest(&asset_id, &investor2, &200, &payment_token, &20000);
+
+        // 100 units over 300 tokens used to round down to zero per token
+        client.create_distribution(&asset_id, &100, &dist_token);
+        assert_eq!(client.get_claimable(&asset_id, &dist_token, &investor1), 33);
+        assert_eq!(client.get_claimable(&asset_id, &dist_token, &investor2), 66);
+        // 100 * REWARD_SCALE leaves 100 scaled units over after dividing by 300
+        assert_eq!(client.get_reward_dust(&asset_id, &dist_token), 100);
+        client.claim_all(&asset_id, &investor1);
+
+        // Fractions and the undistributed remainder carry into the next round
+        client.create_distribution(&asset_id, &200, &dist_token);
+        client.claim_all(&asset_id, &investor1);
+        client.claim_all(&asset_id, &investor2);
+
+        assert_eq!(dist_client.balance(&investor1), 100);
+        assert_eq!(dist_client.balance(&investor2), 200);
+        assert_eq!(dist_client.balance(&client.address), 0);
+        assert_eq!(client.get_reward_dust(&asset_id, &dist_token), 0);
+    }
+
+    #[test]
+    fn test_rewards_settle_on_transfer() {
+        let (env, _admin, investor1, investor2, payment_token, dist_token, client, asset_id) =
+            setup_market();
+
+        client.invest(&asset_id, &investor1, &1000, &payment_token, &100000);
+        client.create_distribution(&asset_id, &1000, &dist_token);
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        client.transfer(&asset_id, &investor1, &investor2, &500);
+        client.create_distribution(&asset_id, &1000, &dist_token);
+
+        assert_eq!(client.get_claimable(&asset_id, &dist_token, &investor1), 1500);
+        assert_eq!(client.get_claimable(&asset_id, &dist_token, &investor2), 500);
+    }
+
+    #[test]
+    fn test_claim_all_pays_every_distribution_token() {
+        let (env, admin, investor1, _, payment_token, dist_token, client, asset_id) = setup_market();
+        let (other_token, other_client, other_admin) = create_token_contract(&env, &admin);
+        other_admin.mint(&admin, &1000000);
+
+        client.invest(&asset_id, &investor1, &1000, &payment_token, &100000);
+        let first = client.create_distribution(&asset_id, &300, &dist_token);
+        let second = client.create_distribution(&asset_id, &700, &dist_token);
+        client.create_distribution(&asset_id, &500, &other_token);
+
+        let paid = client.claim_all(&asset_id, &investor1);
+        assert_eq!(paid.get(dist_token.clone()), Some(1000));
+        assert_eq!(paid.get(other_token.clone()), Some(500));
+        assert_eq!(other_client.balance(&investor1), 500);
+
+        assert!(client.is_distribution_claimed(&first, &investor1));
+        assert!(client.is_distribution_claimed(&second, &investor1));
+        assert!(client.try_claim_all(&asset_id, &investor1).is_err());
+    }
+
+    // ============== Distribution Expiry Tests ==============
+
+    #[test]
+    fn test_distribution_status_tracks_claims() {
+        let (_env, _admin, investor1, investor2, payment_token, dist_token, client, asset_id) =
+            setup_market();
+
+        client.invest(&asset_id, &investor1, &1000, &payment_token, &100000);
+        client.invest(&asset_id, &investor2, &3000, &payment_token, &300000);
+        let distribution_id = client.create_distribution(&asset_id, &4000, &dist_token);
+
+        client.claim_distribution(&distribution_id, &investor1);
+
+        let status = client.get_distribution_status(&distribution_id).unwrap();
+        assert_eq!(status.total_amount, 4000);
+        assert_eq!(status.claimed_amount, 1000);
+        assert_eq!(status.claimant_count, 1);
+        assert_eq!(status.remaining, 3000);
+        assert_eq!(status.claim_deadline, 1000 + 365 * 86400);
+        assert!(!status.is_reclaimed);
+    }
+
+    #[test]
+    fn test_reclaim_unclaimed_after_deadline() {
+        let (env, admin, investor1, investor2, payment_token, dist_token, client, asset_id) =
+            setup_market();
+        let dist_client = TokenClient::new(&env, &dist_token);
+        client.set_claim_period(&admin, &86400);
+
+        client.invest(&asset_id, &investor1, &1000, &payment_token, &100000);
+        client.invest(&asset_id, &investor2, &3000, &payment_token, &300000);
+        let admin_before = dist_client.balance(&admin);
+        let distribution_id = client.create_distribution(&asset_id, &4000, &dist_token);
+
+        client.claim_distribution(&distribution_id, &investor1);
+        assert!(client.try_reclaim_distribution(&distribution_id).is_err());
+
+        env.ledger().with_mut(|li| li.timestamp += 86401);
+        assert!(client.try_claim_distribution(&distribution_id, &investor2).is_err());
+        assert_eq!(client.get_claimable(&asset_id, &dist_token, &investor2), 0);
+
+        assert_eq!(client.reclaim_distribution(&distribution_id), 3000);
+        assert_eq!(dist_client.balance(&admin), admin_before - 1000);
+        assert_eq!(dist_client.balance(&client.address), 0);
+        assert!(client.try_reclaim_distribution(&distribution_id).is_err());
+
+        let status = client.get_distribution_status(&distribution_id).unwrap();
+        assert_eq!(status.remaining, 0);
+        assert!(status.is_reclaimed);
+    }
+
+    #[test]
+    fn test_share_credited_on_transfer_expires_with_deadline() {
+        let (env, admin, investor1, investor2, payment_token, dist_token, client, asset_id) =
+            setup_market();
+        client.set_claim_period(&admin, &86400);
+
+        client.invest(&asset_id, &investor1, &1000, &payment_token, &100000);
+        client.invest(&asset_id, &investor2, &1000, &payment_token, &100000);
+        let expired_id = client.create_distribution(&asset_id, &2000, &dist_token);
+
+        // Moving tokens settles both parties' shares but is not a claim
+        client.transfer(&asset_id, &investor1, &investor2, &500);
+        assert_eq!(client.get_distribution_status(&expired_id).unwrap().claimant_count, 0);
+        env.ledger().with_mut(|li| li.timestamp += 86401);
+
+        // Nobody claimed before the deadline, so the issuer gets it all back
+        assert_eq!(client.get_claimable(&asset_id, &dist_token, &investor1), 0);
+        assert_eq!(client.reclaim_distribution(&expired_id), 2000);
+
+        // A later distribution pays only its own share
+        let live_id = client.create_distribution(&asset_id, &2000, &dist_token);
+        assert_eq!(client.claim_distribution(&live_id, &investor1), 500);
+        assert_eq!(client.claim_all(&asset_id, &investor2).get(dist_token.clone()), Some(1500));
+
+        let status = client.get_distribution_status(&live_id).unwrap();
+        assert_eq!(status.claimant_count, 2);
+        assert_eq!(status.claimed_amount, 2000);
+        assert_eq!(client.get_distribution_status(&expired_id).unwrap().claimant_count, 0);
+    }
+
+    // ============== Redemption Tests ==============
+
+    #[test]
+    fn test_redemptions_settle_in_order_at_nav() {
+        let (env, admin, investor1, investor2, payment_token, _, client, asset_id) = setup_market();
+        let payment_client = TokenClient::new(&env, &payment_token);
+        StellarAssetClient::new(&env, &payment_token).mint(&admin, &200000);
+
+        client.invest(&asset_id, &investor1, &1000, &payment_token, &100000);
+        client.invest(&asset_id, &investor2, &1000, &payment_token, &100000);
+
+        // NAV is 10000000 / 100000 = 100 per token, less a 1% fee
+        client.set_redemption_window(&asset_id, &payment_token, &100, &true);
+        client.fund_redemptions(&asset_id, &150000);
+
+        let first = client.request_redemption(&asset_id, &investor1, &1000);
+        let second = client.request_redemption(&asset_id, &investor2, &1000);
+        assert_eq!(client.get_holding(&asset_id, &investor1).unwrap().amount, 0);
+
+        // Only the first request fits in the funded liquidity
+        let investor1_before = payment_client.balance(&investor1);
+        assert_eq!(client.settle_redemptions(&asset_id, &10), 1);
+        assert_eq!(payment_client.balance(&investor1), investor1_before + 99000);
+        assert_eq!(client.get_redemption(&first).unwrap().status, RedemptionStatus::Settled);
+        assert_eq!(client.get_redemption(&second).unwrap().status, RedemptionStatus::Pending);
+        assert_eq!(client.get_redemption_liquidity(&asset_id), 51000);
+
+        client.fund_redemptions(&asset_id, &50000);
+        assert_eq!(client.settle_redemptions(&asset_id, &10), 1);
+        assert_eq!(client.get_redemption(&second).unwrap().payout, 99000);
+        assert_eq!(client.get_redemption_queue(&asset_id).len(), 0);
+
+        let asset = client.get_asset(&asset_id).unwrap();
+        assert_eq!(asset.circulating_supply, 0);
+        assert_eq!(client.get_redemption_liquidity(&asset_id), 2000);
+    }
+
+    #[test]
+    fn test_cancel_redemption_returns_tokens() {
+        let (_env, _admin, investor1, _, payment_token, _, client, asset_id) = setup_market();
+
+        client.invest(&asset_id, &investor1, &1000, &payment_token, &100000);
+        client.set_redemption_window(&asset_id, &payment_token, &0, &true);
+
+        let request_id = client.request_redemption(&asset_id, &investor1, &400);
+        assert_eq!(client.get_holding(&asset_id, &investor1).unwrap().amount, 600);
+
+        client.cancel_redemption(&request_id);
+        assert_eq!(client.get_holding(&asset_id, &investor1).unwrap().amount, 1000);
+        assert_eq!(client.get_redemption(&request_id).unwrap().status, RedemptionStatus::Cancelled);
+        assert_eq!(client.get_redemption_queue(&asset_id).len(), 0);
+        assert!(client.try_cancel_redemption(&request_id).is_err());
+    }
+
+    #[test]
+    fn test_cancel_redemption_respects_holder_cap() {
+        let (env, admin, investor1, investor2, payment_token, _, client, asset_id) = setup_market();
+        let country_us = String::from_str(&env, "US");
+        client.set_country_holder_cap(&admin, &asset_id, &country_us, &1);
+
+        client.invest(&asset_id, &investor1, &500, &payment_token, &50000);
+        client.set_redemption_window(&asset_id, &payment_token, &0, &true);
+        let request_id = client.request_redemption(&asset_id, &investor1, &500);
+
+        // The freed slot is taken while the redemption is pending
+        client.invest(&asset_id, &investor2, &100, &payment_token, &10000);
+        assert!(client.try_cancel_redemption(&request_id).is_err());
+        assert_eq!(client.get_country_holder_count(&asset_id, &country_us), 1);
+        assert_eq!(client.get_redemption(&request_id).unwrap().status, RedemptionStatus::Pending);
+    }
+
+    #[test]
+    fn test_redemption_window_closed() {
+        let (_env, _admin, investor1, _, payment_token, _, client, asset_id) = setup_market();
+
+        client.invest(&asset_id, &investor1, &1000, &payment_token, &100000);
+        assert!(client.try_request_redemption(&asset_id, &investor1, &100).is_err());
+
+        client.set_redemption_window(&asset_id, &payment_token, &0, &false);
+        assert!(client.try_request_redemption(&asset_id, &investor1, &100).is_err());
+        assert!(client.try_request_redemption(&asset_id, &investor1, &2000).is_err());
+    }
+
+    // ============== Lock-up Tests ==============
+
+    #[test]
+    fn test_lockup_tracked_per_tranche() {
+        let (env, _admin, investor1, investor2, payment_token, _, client, asset_id) = setup_market();
+        client.set_lockup_period(&asset_id, &1000);
+
+        client.invest(&asset_id, &investor1, &500, &payment_token, &50000);
+        env.ledger().with_mut(|li| li.timestamp = 1600);
+        client.invest(&asset_id, &investor1, &300, &payment_token, &30000);
+
+        assert_eq!(client.get_lockups(&asset_id, &investor1).len(), 2);
+        assert_eq!(client.get_transferable_balance(&asset_id, &investor1), 0);
+        assert!(client.try_transfer(&asset_id, &investor1, &investor2, &100).is_err());
+
+        // The later purchase does not push back the first tranche's unlock
+        env.ledger().with_mut(|li| li.timestamp = 2001);
+        assert_eq!(client.get_transferable_balance(&asset_id, &investor1), 500);
+        assert!(client.try_transfer(&asset_id, &investor1, &investor2, &600).is_err());
+        client.transfer(&asset_id, &investor1, &investor2, &500);
+
+        // Received tokens are not locked for the recipient
+        assert_eq!(client.get_transferable_balance(&asset_id, &investor2), 500);
+
+        env.ledger().with_mut(|li| li.timestamp = 2601);
+        assert_eq!(client.get_transferable_balance(&asset_id, &investor1), 300);
+    }
+
+    #[test]
+    fn test_release_lockup_early() {
+        let (env, admin, investor1, investor2, payment_token, _, client, asset_id) = setup_market();
+        client.set_lockup_period(&asset_id, &86400);
+
+        client.invest(&asset_id, &investor1, &500, &payment_token, &50000);
+        assert_eq!(client.get_holding(&asset_id, &investor1).unwrap().locked_until, 1000 + 86400);
+
+        // Compliance agents cannot override a lock-up
+        let agent = Address::generate(&env);
+        client.grant_role(&admin, &Role::ComplianceOfficer, &agent);
+        assert!(client.try_release_lockup(&agent, &asset_id, &investor1).is_err());
+
+        client.release_lockup(&admin, &asset_id, &investor1);
+        assert_eq!(client.get_transferable_balance(&asset_id, &investor1), 500);
+        assert_eq!(client.get_holding(&asset_id, &investor1).unwrap().locked_until, 0);
+        assert!(client.transfer(&asset_id, &investor1, &investor2, &500));
+    }
+
+    #[test]
+    fn test_locked_tokens_cannot_be_redeemed() {
+        let (_env, _admin, investor1, _, payment_token, _, client, asset_id) = setup_market();
+        client.set_lockup_period(&asset_id, &86400);
+        client.set_redemption_window(&asset_id, &payment_token, &0, &true);
+
+        client.invest(&asset_id, &investor1, &500, &payment_token, &50000);
+        assert!(client.try_request_redemption(&asset_id, &investor1, &100).is_err());
+    }
+
+    // ============== Jurisdiction Tests ==============
+
+    #[test]
+    fn test_blocked_country_cannot_invest_or_receive() {
+        let (env, admin, investor1, investor2, payment_token, _, client, asset_id) = setup_market();
+        let country_uk = String::from_str(&env, "UK");
+        let investor3 = Address::generate(&env);
+        client.whitelist_country(&admin, &country_uk, &true);
+        client.register_investor(&admin, &investor3, &false, &country_uk, &(1000 + 365 * 86400));
+
+        let mut blocked = Vec::new(&env);
+        blocked.push_back(country_uk.clone());
+        client.set_jurisdiction_rules(&admin, &asset_id, &Vec::new(&env), &blocked, &0);
+
+        assert_eq!(client.check_eligibility(&asset_id, &investor3), Eligibility::CountryBlocked);
+        assert!(client.try_invest(&asset_id, &investor3, &100, &payment_token, &10000).is_err());
+
+        client.invest(&asset_id, &investor1, &500, &payment_token, &50000);
+        assert!(client.try_transfer(&asset_id, &investor1, &investor3, &100).is_err());
+
+        // An allow list shuts out every other country
+        let mut allowed = Vec::new(&env);
+        allowed.push_back(String::from_str(&env, "DE"));
+        client.set_jurisdiction_rules(&admin, &asset_id, &allowed, &Vec::new(&env), &0);
+        assert_eq!(client.check_eligibility(&asset_id, &investor2), Eligibility::CountryNotAllowed);
+    }
+
+    #[test]
+    fn test_country_change_rejected_while_holding() {
+        let (env, admin, investor1, investor2, payment_token, _, client, asset_id) = setup_market();
+        let country_de = String::from_str(&env, "DE");
+        client.whitelist_country(&admin, &country_de, &true);
+        let kyc_expiry = env.ledger().timestamp() + 365 * 86400;
+
+        client.invest(&asset_id, &investor1, &500, &payment_token, &50000);
+        assert!(client.try_register_investor(&admin, &investor1, &false, &country_de, &kyc_expiry).is_err());
+
+        // Renewing KYC in the same country is still fine
+        let country_us = String::from_str(&env, "US");
+        assert!(client.register_investor(&admin, &investor1, &false, &country_us, &kyc_expiry));
+
+        // Once out of the asset the country can change
+        client.transfer(&asset_id, &investor1, &investor2, &500);
+        assert!(client.register_investor(&admin, &investor1, &false, &country_de, &kyc_expiry));
+        assert_eq!(client.get_country_holder_count(&asset_id, &country_us), 1);
+        assert_eq!(client.get_country_holder_count(&asset_id, &country_de), 0);
+    }
+
+    #[test]
+    fn test_max_holders_cap() {
+        let (env, admin, investor1, investor2, payment_token, _, client, asset_id) = setup_market();
+        client.set_jurisdiction_rules(&admin, &asset_id, &Vec::new(&env), &Vec::new(&env), &1);
+
+        client.invest(&asset_id, &investor1, &500, &payment_token, &50000);
+        assert_eq!(client.get_holder_count(&asset_id), 1);
+        assert_eq!(client.check_eligibility(&asset_id, &investor2), Eligibility::MaxHoldersReached);
+        assert!(client.try_invest(&asset_id, &investor2, &100, &payment_token, &10000).is_err());
+        assert!(client.try_transfer(&asset_id, &investor1, &investor2, &100).is_err());
+
+        // Existing holders can top up, and a full exit frees the slot
+        client.invest(&asset_id, &investor1, &100, &payment_token, &10000);
+        client.set_jurisdiction_rules(&admin, &asset_id, &Vec::new(&env), &Vec::new(&env), &2);
+        client.transfer(&asset_id, &investor1, &investor2, &600);
+        assert_eq!(client.get_holder_count(&asset_id), 1);
+        client.set_jurisdiction_rules(&admin, &asset_id, &Vec::new(&env), &Vec::new(&env), &1);
+        assert_eq!(client.check_eligibility(&asset_id, &investor1), Eligibility::MaxHoldersReached);
+    }
+
+    #[test]
+    fn test_country_holder_cap() {
+        let (env, admin, investor1, investor2, payment_token, _, client, asset_id) = setup_market();
+        let country_us = String::from_str(&env, "US");
+        client.set_country_holder_cap(&admin, &asset_id, &country_us, &1);
+
+        client.invest(&asset_id, &investor1, &500, &payment_token, &50000);
+        assert_eq!(client.get_country_holder_count(&asset_id, &country_us), 1);
+        assert_eq!(client.check_eligibility(&asset_id, &investor2), Eligibility::CountryCapReached);
+        assert!(client.try_invest(&asset_id, &investor2, &100, &payment_token, &10000).is_err());
+
+        client.set_country_holder_cap(&admin, &asset_id, &country_us, &0);
+        assert!(client.invest(&asset_id, &investor2, &100, &payment_token, &10000));
+        assert_eq!(client.get_country_holder_count(&asset_id, &country_us), 2);
+    }
+
+    // ============== Compliance Agent Tests ==============
+
+    #[test]
+    fn test_forced_transfer_moves_locked_tokens() {
+        let (env, admin, investor1, investor2, payment_token, _, client, asset_id) = setup_market();
+        let agent = Address::generate(&env);
+        client.grant_role(&admin, &Role::ComplianceOfficer, &agent);
+        client.set_lockup_period(&asset_id, &86400);
+
+        client.invest(&asset_id, &investor1, &500, &payment_token, &50000);
+        assert!(client.try_forced_transfer(&investor2, &asset_id, &investor1, &investor2, &200, &1).is_err());
+
+        client.forced_transfer(&agent, &asset_id, &investor1, &investor2, &200, &1);
+        assert_eq!(client.get_holding(&asset_id, &investor1).unwrap().amount, 300);
+        assert_eq!(client.get_holding(&asset_id, &investor2).unwrap().amount, 200);
+
+        // Moved tokens stay locked for the recipient
+        assert_eq!(client.get_transferable_balance(&asset_id, &investor2), 0);
+        assert_eq!(client.get_lockups(&asset_id, &investor2).get(0).unwrap().unlocks_at, 1000 + 86400);
+        assert_eq!(client.get_lockups(&asset_id, &investor1).get(0).unwrap().amount, 300);
+    }
+
+    #[test]
+    fn test_forced_transfer_requires_accredited_recipient() {
+        let (env, admin, investor1, investor2, payment_token, _, client, _) = setup_market();
+        let agent = Address::generate(&env);
+        client.grant_role(&admin, &Role::ComplianceOfficer, &agent);
+
+        let asset_contract = env.register_stellar_asset_contract_v2(admin.clone());
+        asset_contract.issuer().set_flag(IssuerFlags::RevocableFlag);
+        asset_contract.issuer().set_flag(IssuerFlags::ClawbackEnabledFlag);
+        StellarAssetClient::new(&env, &asset_contract.address()).mint(&admin, &100000);
+        client.grant_role(&admin, &Role::Custodian, &admin);
+        let asset_id = client.create_asset(
+            &admin,
+            &String::from_str(&env, "Accredited Asset"),
+            &String::from_str(&env, "ACC"),
+            &AssetType::RealEstate,
+            &100000,
+            &10000000,
+            &admin,
+            &asset_contract.address(),
+            &ComplianceTerms { min_investment: 100, accredited_only: true },
+        );
+
+        client.update_accreditation(&admin, &investor1, &true);
+        client.invest(&asset_id, &investor1, &500, &payment_token, &50000);
+
+        assert!(client.try_forced_transfer(&agent, &asset_id, &investor1, &investor2, &200, &1).is_err());
+
+        client.update_accreditation(&admin, &investor2, &true);
+        client.forced_transfer(&agent, &asset_id, &investor1, &investor2, &200, &1);
+        assert_eq!(client.get_holding(&asset_id, &investor2).unwrap().amount, 200);
+    }
+
+    #[test]
+    fn test_recover_address_migrates_position() {
+        let (env, admin, investor1, _, payment_token, dist_token, client, asset_id) = setup_market();
+        let agent = Address::generate(&env);
+        let new_wallet = Address::generate(&env);
+        client.grant_role(&admin, &Role::ComplianceOfficer, &agent);
+        client.set_lockup_period(&asset_id, &86400);
+
+        let terms = String::from_str(&env, "Subscription Agreement");
+        let uri = String::from_str(&env, "ipfs://terms");
+        client.set_document(&asset_id, &terms, &uri, &document_hash(&env, "v1"), &true);
+        client.acknowledge_document(&asset_id, &investor1, &terms, &document_hash(&env, "v1"));
+
+        client.invest(&asset_id, &investor1, &1000, &payment_token, &100000);
+        client.create_distribution(&asset_id, &500, &dist_token);
+
+        // Leaving a held asset out would strand it on the lost wallet
+        assert!(client.try_recover_address(&agent, &investor1, &new_wallet, &Vec::new(&env), &7).is_err());
+        assert_eq!(client.recover_address(&agent, &investor1, &new_wallet, &vec![&env, asset_id], &7), 1);
+
+        assert!(client.get_holding(&asset_id, &investor1).is_none());
+        assert!(client.get_investor(&investor1).is_none());
+        assert_eq!(client.get_investor(&new_wallet).unwrap().address, new_wallet);
+        assert_eq!(client.get_holding(&asset_id, &new_wallet).unwrap().amount, 1000);
+        assert_eq!(client.get_lockups(&asset_id, &new_wallet).len(), 1);
+        assert_eq!(client.get_holder_count(&asset_id), 1);
+
+        assert_eq!(client.get_claimable(&asset_id, &dist_token, &new_wallet), 500);
+        assert_eq!(client.claim_all(&asset_id, &new_wallet).get(dist_token.clone()), Some(500));
+        assert_eq!(client.balance_of_at(&asset_id, &investor1, &1000), 0);
+
+        // The acknowledgement follows the investor
+        StellarAssetClient::new(&env, &payment_token).mint(&new_wallet, &10000);
+        assert!(client.invest(&asset_id, &new_wallet, &100, &payment_token, &10000));
+    }
+
+    // ============== Partial Freeze Tests ==============
+
+    #[test]
+    fn test_partial_freeze_limits_transfer_and_redemption() {
+        let (env, admin, investor1, investor2, payment_token, _, client, asset_id) = setup_market();
+        let agent = Address::generate(&env);
+        client.grant_role(&admin, &Role::ComplianceOfficer, &agent);
+        client.set_redemption_window(&asset_id, &payment_token, &0, &true);
+
+        client.invest(&asset_id, &investor1, &1000, &payment_token, &100000);
+        assert!(client.try_freeze_partial(&agent, &asset_id, &investor1, &1001).is_err());
+        client.freeze_partial(&agent, &asset_id, &investor1, &600);
+
+        assert_eq!(client.get_frozen_balance(&asset_id, &investor1), 600);
+        assert_eq!(client.get_transferable_balance(&asset_id, &investor1), 400);
+        assert!(client.try_transfer(&asset_id, &investor1, &investor2, &500).is_err());
+        assert!(client.try_request_redemption(&asset_id, &investor1, &500).is_err());
+
+        client.transfer(&asset_id, &investor1, &investor2, &300);
+        client.request_redemption(&asset_id, &investor1, &100);
+        assert_eq!(client.get_transferable_balance(&asset_id, &investor1), 0);
+
+        client.unfreeze_partial(&agent, &asset_id, &investor1, &200);
+        assert_eq!(client.get_frozen_balance(&asset_id, &investor1), 400);
+        client.transfer(&asset_id, &investor1, &investor2, &200);
+        assert!(client.try_unfreeze_partial(&agent, &asset_id, &investor1, &500).is_err());
+    }
+
+    #[test]
+    fn test_freeze_overlaps_lockup() {
+        let (env, admin, investor1, investor2, payment_token, _, client, asset_id) = setup_market();
+        let agent = Address::generate(&env);
+        client.grant_role(&admin, &Role::ComplianceOfficer, &agent);
+        client.set_lockup_period(&asset_id, &86400);
+
+        client.invest(&asset_id, &investor1, &500, &payment_token, &50000);
+        env.ledger().with_mut(|li| li.timestamp += 86401);
+        client.invest(&asset_id, &investor1, &500, &payment_token, &50000);
+
+        // 500 locked and 600 frozen out of 1000 withholds 600, not 1100
+        client.freeze_partial(&agent, &asset_id, &investor1, &600);
+        assert_eq!(client.get_transferable_balance(&asset_id, &investor1), 400);
+        assert!(client.try_transfer(&asset_id, &investor1, &investor2, &401).is_err());
+        client.transfer(&asset_id, &investor1, &investor2, &400);
+
+        assert!(client.try_freeze_partial(&agent, &(asset_id + 1), &investor1, &100).is_err());
+    }
+
+    #[test]
+    fn test_freeze_requires_compliance_agent() {
+        let (_env, _admin, investor1, investor2, payment_token, _, client, asset_id) = setup_market();
+
+        client.invest(&asset_id, &investor1, &1000, &payment_token, &100000);
+        assert!(client.try_freeze_partial(&investor2, &asset_id, &investor1, &100).is_err());
+        assert_eq!(client.get_frozen_balance(&asset_id, &investor1), 0);
+    }
+
+    #[test]
+    fn test_forced_transfer_clears_excess_freeze() {
+        let (env, admin, investor1, investor2, payment_token, _, client, asset_id) = setup_market();
+        let agent = Address::generate(&env);
+        client.grant_role(&admin, &Role::ComplianceOfficer, &agent);
+
+        client.invest(&asset_id, &investor1, &1000, &payment_token, &100000);
+        client.freeze_partial(&agent, &asset_id, &investor1, &800);
+        client.forced_transfer(&agent, &asset_id, &investor1, &investor2, &700, &2);
+
+        assert_eq!(client.get_frozen_balance(&asset_id, &investor1), 300);
+        assert_eq!(client.get_frozen_balance(&asset_id, &investor2), 0);
+    }
+
+    // ============== Role Tests ==============
+
+    #[test]
+    fn test_roles_gate_privileged_calls() {
+        let (env, admin, investor1, _, _, _, client, _) = setup_market();
+        let officer = Address::generate(&env);
+        let newcomer = Address::generate(&env);
+        let country_code = String::from_str(&env, "US");
+        let kyc_expiry = 1000 + 365 * 86400;
+
+        assert!(client.try_register_investor(&officer, &newcomer, &false, &country_code, &kyc_expiry).is_err());
+        assert!(client.try_grant_role(&officer, &Role::ComplianceOfficer, &officer).is_err());
+
+        client.grant_role(&admin, &Role::ComplianceOfficer, &officer);
+        assert!(client.has_role(&Role::ComplianceOfficer, &officer));
+        assert!(client.register_investor(&officer, &newcomer, &false, &country_code, &kyc_expiry));
+        assert!(client.try_submit_valuation(&officer, &1, &20000000).is_err());
+
+        client.revoke_role(&admin, &Role::ComplianceOfficer, &officer);
+        assert!(!client.has_role(&Role::ComplianceOfficer, &officer));
+        assert!(client.try_blacklist_address(&officer, &investor1, &true).is_err());
+
+        // The primary admin cannot lose platform admin through revoke_role
+        client.revoke_role(&admin, &Role::PlatformAdmin, &admin);
+        assert!(client.has_role(&Role::PlatformAdmin, &admin));
+    }
+
+    #[test]
+    fn test_issuer_limited_to_own_assets() {
+        let (env, admin, _, _, _, _, client, admin_asset) = setup_market();
+        let issuer = Address::generate(&env);
+        let custodian = Address::generate(&env);
+        let (token_address, _, _) = create_token_contract(&env, &admin);
+
+        client.grant_role(&admin, &Role::Custodian, &custodian);
+        assert!(client.try_create_asset(
+            &issuer,
+            &String::from_str(&env, "Issuer Asset"),
+            &String::from_str(&env, "ISS"),
+            &AssetType::Bond,
+            &1000,
+            &100000,
+            &custodian,
+            &token_address,
+            &ComplianceTerms { min_investment: 10, accredited_only: false },
+        ).is_err());
+
+        client.grant_role(&admin, &Role::Issuer, &issuer);
+        let issuer_asset = client.create_asset(
+            &issuer,
+            &String::from_str(&env, "Issuer Asset"),
+            &String::from_str(&env, "ISS"),
+            &AssetType::Bond,
+            &1000,
+            &100000,
+            &custodian,
+            &token_address,
+            &ComplianceTerms { min_investment: 10, accredited_only: false },
+        );
+        assert_eq!(client.get_asset(&issuer_asset).unwrap().issuer, issuer);
+
+        // Asset-level calls need the asset's own issuer, not any issuer
+        client.set_lockup_period(&issuer_asset, &86400);
+        assert_eq!(env.auths()[0].0, issuer);
+        client.set_lockup_period(&admin_asset, &86400);
+        assert_eq!(env.auths()[0].0, admin);
+
+        client.revoke_role(&admin, &Role::Issuer, &issuer);
+        assert!(client.try_set_lockup_period(&issuer_asset, &0).is_err());
+    }
+
+    #[test]
+    fn test_custodian_role_controls_asset_activity() {
+        let (_env, admin, investor1, _, payment_token, _, client, asset_id) = setup_market();
+        let custodian = client.get_asset(&asset_id).unwrap().custodian;
+
+        client.revoke_role(&admin, &Role::Custodian, &custodian);
+        assert!(client.try_set_asset_active(&asset_id, &false).is_err());
+
+        client.grant_role(&admin, &Role::Custodian, &custodian);
+        assert!(client.set_asset_active(&asset_id, &false));
+        assert!(!client.get_asset(&asset_id).unwrap().is_active);
+        assert!(client.try_invest(&asset_id, &investor1, &100, &payment_token, &10000).is_err());
+    }
+
+    #[test]
+    fn test_create_asset_requires_custodian_role() {
+        let (env, admin, client) = setup_test_env();
+        let custodian = Address::generate(&env);
+        let (token_address, _, _) = create_token_contract(&env, &admin);
+
+        let result = client.try_create_asset(
+            &admin,
+            &String::from_str(&env, "Vault Gold"),
+            &String::from_str(&env, "VGLD"),
+            &AssetType::Commodity,
+            &1000,
+            &100000,
+            &custodian,
+            &token_address,
+            &ComplianceTerms { min_investment: 10, accredited_only: false },
+        );
+        assert!(result.is_err());
+        assert_eq!(client.get_asset_count(), 0);
+    }
+
+    #[test]
+    fn test_set_admin_moves_operational_roles() {
+        let (env, admin, client) = setup_test_env();
+        let new_admin = Address::generate(&env);
+        assert!(client.has_role(&Role::ComplianceOfficer, &admin));
+
+        client.set_admin(&new_admin);
+
+        for role in [Role::PlatformAdmin, Role::Issuer, Role::ComplianceOfficer, Role::ValuationAgent] {
+            assert!(!client.has_role(&role, &admin));
+            assert!(client.has_role(&role, &new_admin));
+        }
+        let country_code = String::from_str(&env, "US");
+        assert!(client.try_whitelist_country(&admin, &country_code, &true).is_err());
+    }
+
+    // ============== Valuation Oracle Tests ==============
+
+    #[test]
+    fn test_valuation_needs_quorum_within_tolerance() {
+        let (env, admin, _, _, _, _, client, asset_id) = setup_market();
+        let appraiser1 = Address::generate(&env);
+        let appraiser2 = Address::generate(&env);
+        let appraiser3 = Address::generate(&env);
+        client.grant_role(&admin, &Role::ValuationAgent, &appraiser1);
+        client.grant_role(&admin, &Role::ValuationAgent, &appraiser2);
+        client.grant_role(&admin, &Role::ValuationAgent, &appraiser3);
+
+        // Two of three within 5%, at most 50% change per update
+        client.set_valuation_config(&admin, &asset_id, &2, &500, &5000, &0);
+
+        assert!(!client.submit_valuation(&appraiser1, &asset_id, &11000000));
+        assert!(!client.submit_valuation(&appraiser2, &asset_id, &14000000));
+        assert_eq!(client.get_pending_valuations(&asset_id).len(), 2);
+        assert_eq!(client.get_asset(&asset_id).unwrap().asset_value_usd, 10000000);
+
+        // The outlier is left out and the median of the agreeing pair applied
+        env.ledger().with_mut(|li| li.timestamp = 5000);
+        assert!(client.submit_valuation(&appraiser3, &asset_id, &11200000));
+        assert_eq!(client.get_asset(&asset_id).unwrap().asset_value_usd, 11100000);
+        assert_eq!(client.get_pending_valuations(&asset_id).len(), 0);
+        assert_eq!(client.get_tvl(), 11100000);
+
+        let unregistered = Address::generate(&env);
+        assert!(client.try_submit_valuation(&unregistered, &asset_id, &11000000).is_err());
+    }
+
+    #[test]
+    fn test_valuation_max_deviation_rejects_round() {
+        let (env, admin, _, _, _, _, client, asset_id) = setup_market();
+        client.set_valuation_config(&admin, &asset_id, &1, &0, &1000, &0);
+
+        assert!(!client.submit_valuation(&admin, &asset_id, &12000000));
+        assert_eq!(client.get_asset(&asset_id).unwrap().asset_value_usd, 10000000);
+        assert_eq!(client.get_pending_valuations(&asset_id).len(), 0);
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        assert!(client.submit_valuation(&admin, &asset_id, &10900000));
+        assert_eq!(client.get_asset(&asset_id).unwrap().last_valuation, 2000);
+    }
+
+    #[test]
+    fn test_stale_valuation_blocks_invest() {
+        let (env, admin, investor1, _, payment_token, _, client, asset_id) = setup_market();
+        client.set_valuation_config(&admin, &asset_id, &1, &0, &0, &86400);
+
+        client.invest(&asset_id, &investor1, &100, &payment_token, &10000);
+
+        env.ledger().with_mut(|li| li.timestamp = 1000 + 86401);
+        assert!(client.try_invest(&asset_id, &investor1, &100, &payment_token, &10000).is_err());
+
+        client.submit_valuation(&admin, &asset_id, &10000000);
+        assert!(client.invest(&asset_id, &investor1, &100, &payment_token, &10000));
+    }
+
+    #[test]
+    fn test_valuation_history_by_timestamp() {
+        let (env, admin, _, _, _, _, client, asset_id) = setup_market();
+        client.set_valuation_config(&admin, &asset_id, &1, &0, &0, &0);
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        client.submit_valuation(&admin, &asset_id, &12000000);
+        env.ledger().with_mut(|li| li.timestamp = 3000);
+        client.submit_valuation(&admin, &asset_id, &13000000);
+
+        assert_eq!(client.get_valuation_history(&asset_id).len(), 3);
+        assert!(client.get_valuation_at(&asset_id, &999).is_none());
+        assert_eq!(client.get_valuation_at(&asset_id, &1500).unwrap().value_usd, 10000000);
+        assert_eq!(client.get_valuation_at(&asset_id, &2999).unwrap().value_usd, 12000000);
+        assert_eq!(client.get_valuation_at(&asset_id, &3000).unwrap().attestations, 1);
+    }
+
+    // ============== Document Registry Tests ==============
+
+    fn document_hash(env: &Env, contents: &str) -> BytesN<32> {
+        env.crypto().sha256(&Bytes::from_slice(env, contents.as_bytes())).to_bytes()
+    }
+
+    #[test]
+    fn test_document_registry() {
+        let (env, _admin, _, _, _, _, client, asset_id) = setup_market();
+        let memorandum = String::from_str(&env, "offering-memorandum");
+        let deed = String::from_str(&env, "title-deed");
+        let uri = String::from_str(&env, "ipfs://memorandum-v1");
+
+        client.set_document(&asset_id, &memorandum, &uri, &document_hash(&env, "v1"), &false);
+        client.set_document(
+            &asset_id,
+            &deed,
+            &String::from_str(&env, "ipfs://deed"),
+            &document_hash(&env, "deed"),
+            &false,
+        );
+
+        env.ledger().with_mut(|li| li.timestamp = 2000);
+        let uri_v2 = String::from_str(&env, "ipfs://memorandum-v2");
+        client.set_document(&asset_id, &memorandum, &uri_v2, &document_hash(&env, "v2"), &false);
+
+        let documents = client.get_documents(&asset_id);
+        assert_eq!(documents.len(), 2);
+        let document = client.get_document(&asset_id, &memorandum).unwrap();
+        assert_eq!(document.uri, uri_v2);
+        assert_eq!(document.content_hash, document_hash(&env, "v2"));
+        assert_eq!(document.last_modified, 2000);
+
+        client.remove_document(&asset_id, &deed);
+        assert_eq!(client.get_documents(&asset_id).len(), 1);
+        assert!(client.get_document(&asset_id, &deed).is_none());
+        assert!(client.try_remove_document(&asset_id, &deed).is_err());
+    }
+
+    #[test]
+    fn test_invest_requires_current_acknowledgement() {
+        let (env, _admin, investor1, _, payment_token, _, client, asset_id) = setup_market();
+        let memorandum = String::from_str(&env, "offering-memorandum");
+        let uri = String::from_str(&env, "ipfs://memorandum");
+        let v1 = document_hash(&env, "v1");
+        client.set_document(&asset_id, &memorandum, &uri, &v1, &true);
+
+        assert!(client.try_invest(&asset_id, &investor1, &100, &payment_token, &10000).is_err());
+        assert!(client.try_acknowledge_document(&asset_id, &investor1, &memorandum, &document_hash(&env, "v0")).is_err());
+
+        client.acknowledge_document(&asset_id, &investor1, &memorandum, &v1);
+        assert!(client.has_acknowledged(&asset_id, &memorandum, &investor1));
+        assert!(client.invest(&asset_id, &investor1, &100, &payment_token, &10000));
+
+        // A new version needs a new acknowledgement
+        let v2 = document_hash(&env, "v2");
+        client.set_document(&asset_id, &memorandum, &uri, &v2, &true);
+        assert!(!client.has_acknowledged(&asset_id, &memorandum, &investor1));
+        assert!(client.try_invest(&asset_id, &investor1, &100, &payment_token, &10000).is_err());
+
+        client.acknowledge_document(&asset_id, &investor1, &memorandum, &v2);
+        assert!(client.invest(&asset_id, &investor1, &100, &payment_token, &10000));
+    }
 }
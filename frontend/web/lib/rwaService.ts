@@ -153,6 +153,14 @@ function assetTypeToScVal(assetType: AssetType): any {
   return nativeToScVal({ [typeMap[assetType]]: [] }, { type: 'symbol' });
 }
 
+function complianceTermsToScVal(minInvestment: string, accreditedOnly: boolean): any {
+  // Contract structs are maps keyed by field name, in sorted order
+  return nativeToScVal(
+    { accredited_only: accreditedOnly, min_investment: BigInt(minInvestment) },
+    { type: { accredited_only: ['symbol', 'bool'], min_investment: ['symbol', 'i128'] } }
+  );
+}
+
 async function waitForTransaction(hash: string): Promise<rpc.Api.GetTransactionResponse> {
   let status = await server.getTransaction(hash);
   let attempts = 0;
@@ -223,7 +231,9 @@ class RWAService {
   }
 
   /**
-   * Create/tokenize a new real-world asset (admin only)
+   * Create/tokenize a new real-world asset. The signer becomes the asset's
+   * issuer and must hold the Issuer role; the custodian must hold the
+   * Custodian role.
    */
   async createAsset(
     adminKeypair: Keypair,
@@ -249,6 +259,7 @@ class RWAService {
         .addOperation(
           contract.call(
             'create_asset',
+            nativeToScVal(Address.fromString(adminKeypair.publicKey())),
             nativeToScVal(params.name, { type: 'string' }),
             nativeToScVal(params.symbol, { type: 'string' }),
             assetTypeToScVal(params.assetType),
@@ -256,8 +267,7 @@ class RWAService {
             nativeToScVal(BigInt(params.assetValueUsd), { type: 'i128' }),
             nativeToScVal(Address.fromString(params.custodian)),
             nativeToScVal(Address.fromString(params.tokenAddress)),
-            nativeToScVal(BigInt(params.minInvestment), { type: 'i128' }),
-            nativeToScVal(params.accreditedOnly, { type: 'bool' })
+            complianceTermsToScVal(params.minInvestment, params.accreditedOnly)
           )
         )
         .setTimeout(30)
@@ -296,12 +306,16 @@ class RWAService {
   }
 
   /**
-   * Update asset valuation (admin only)
+   * Set how appraisals of an asset are accepted (admin only). Appraisals are
+   * refused until an asset has a valuation config.
    */
-  async updateValuation(
+  async setValuationConfig(
     adminKeypair: Keypair,
     assetId: string,
-    newValueUsd: string
+    quorum: number,
+    toleranceBps: number,
+    maxDeviationBps: number,
+    stalenessWindowSecs: number
   ): Promise<{ success: boolean; txHash?: string; error?: string }> {
     try {
       const contract = getRWAContract();
@@ -313,9 +327,13 @@ class RWAService {
       })
         .addOperation(
           contract.call(
-            'update_valuation',
+            'set_valuation_config',
+            nativeToScVal(Address.fromString(adminKeypair.publicKey())),
             nativeToScVal(BigInt(assetId), { type: 'u64' }),
-            nativeToScVal(BigInt(newValueUsd), { type: 'i128' })
+            nativeToScVal(quorum, { type: 'u32' }),
+            nativeToScVal(toleranceBps, { type: 'u32' }),
+            nativeToScVal(maxDeviationBps, { type: 'u32' }),
+            nativeToScVal(BigInt(stalenessWindowSecs), { type: 'u64' })
           )
         )
         .setTimeout(30)
@@ -334,27 +352,87 @@ class RWAService {
       const status = await waitForTransaction(result.hash);
 
       if (status.status === 'SUCCESS') {
+        return { success: true, txHash: result.hash };
+      }
+
+      return { success: false, error: `Transaction failed with status: ${status.status}` };
+    } catch (error: any) {
+      console.error('Failed to set valuation config:', error);
+      return { success: false, error: error.message };
+    }
+  }
+
+  /**
+   * Submit an appraisal (valuation agent only). The asset's value only
+   * changes once a quorum of appraisals agrees; `applied` reports whether
+   * this submission completed one.
+   */
+  async submitValuation(
+    appraiserKeypair: Keypair,
+    assetId: string,
+    newValueUsd: string
+  ): Promise<{ success: boolean; applied?: boolean; txHash?: string; error?: string }> {
+    try {
+      const contract = getRWAContract();
+      const appraiserAccount = await server.getAccount(appraiserKeypair.publicKey());
+
+      const transaction = new TransactionBuilder(appraiserAccount, {
+        fee: BASE_FEE,
+        networkPassphrase: NETWORK_PASSPHRASE,
+      })
+        .addOperation(
+          contract.call(
+            'submit_valuation',
+            nativeToScVal(Address.fromString(appraiserKeypair.publicKey())),
+            nativeToScVal(BigInt(assetId), { type: 'u64' }),
+            nativeToScVal(BigInt(newValueUsd), { type: 'i128' })
+          )
+        )
+        .setTimeout(30)
+        .build();
+
+      const simulated = await server.simulateTransaction(transaction);
+
+      if (rpc.Api.isSimulationError(simulated)) {
+        return { success: false, error: `Simulation failed: ${simulated.error}` };
+      }
+
+      const prepared = rpc.assembleTransaction(transaction, simulated).build();
+      prepared.sign(appraiserKeypair);
+
+      const result = await server.sendTransaction(prepared);
+      const status = await waitForTransaction(result.hash);
+
+      if (status.status === 'SUCCESS' && 'returnValue' in status && status.returnValue) {
+        const applied = Boolean(scValToNative(status.returnValue));
+        if (!applied) {
+          return { success: true, applied, txHash: result.hash };
+        }
+
+        // The quorum's median is applied, not necessarily this appraisal
+        const asset = await this.getContractAsset(assetId);
+
         // Update in database
         await supabase
           .from('rwa_assets')
           .update({ 
-            asset_value_usd: newValueUsd, 
+            asset_value_usd: asset ? asset.assetValueUsd : newValueUsd, 
             last_valuation: new Date().toISOString() 
           })
           .eq('asset_id', assetId);
 
-        return { success: true, txHash: result.hash };
+        return { success: true, applied, txHash: result.hash };
       }
 
       return { success: false, error: `Transaction failed with status: ${status.status}` };
     } catch (error: any) {
-      console.error('Failed to update valuation:', error);
+      console.error('Failed to submit valuation:', error);
       return { success: false, error: error.message };
     }
   }
 
   /**
-   * Register an investor with KYC verification (admin only)
+   * Register an investor with KYC verification (compliance officer only)
    */
   async registerInvestor(
     adminKeypair: Keypair,
@@ -376,6 +454,7 @@ class RWAService {
         .addOperation(
           contract.call(
             'register_investor',
+            nativeToScVal(Address.fromString(adminKeypair.publicKey())),
             nativeToScVal(Address.fromString(investorAddress)),
             nativeToScVal(isAccredited, { type: 'bool' }),
             nativeToScVal(countryCode, { type: 'string' }),
@@ -439,6 +518,7 @@ class RWAService {
         .addOperation(
           contract.call(
             'whitelist_country',
+            nativeToScVal(Address.fromString(adminKeypair.publicKey())),
             nativeToScVal(countryCode, { type: 'string' }),
             nativeToScVal(allowed, { type: 'bool' })
           )
@@ -680,38 +760,45 @@ class RWAService {
       }
 
       // Fallback to contract
-      const contract = getRWAContract();
-      const dummyAccount = await this.getDummyAccount();
+      return await this.getContractAsset(assetId);
+    } catch (error: any) {
+      console.error('Failed to get asset:', error);
+      return null;
+    }
+  }
 
-      const transaction = new TransactionBuilder(dummyAccount, {
-        fee: BASE_FEE,
-        networkPassphrase: NETWORK_PASSPHRASE,
-      })
-        .addOperation(
-          contract.call(
-            'get_asset',
-            nativeToScVal(BigInt(assetId), { type: 'u64' })
-          )
+  /**
+   * Read asset details straight from the contract
+   */
+  private async getContractAsset(assetId: string): Promise<RWAAsset | null> {
+    const contract = getRWAContract();
+    const dummyAccount = await this.getDummyAccount();
+
+    const transaction = new TransactionBuilder(dummyAccount, {
+      fee: BASE_FEE,
+      networkPassphrase: NETWORK_PASSPHRASE,
+    })
+      .addOperation(
+        contract.call(
+          'get_asset',
+          nativeToScVal(BigInt(assetId), { type: 'u64' })
         )
-        .setTimeout(30)
-        .build();
+      )
+      .setTimeout(30)
+      .build();
 
-      const simulated = await server.simulateTransaction(transaction);
-
-      if (rpc.Api.isSimulationError(simulated)) {
-        return null;
-      }
-
-      if (simulated.result) {
-        const assetData = scValToNative(simulated.result.retval);
-        return assetData ? this.mapContractAssetToRWAAsset(assetData) : null;
-      }
+    const simulated = await server.simulateTransaction(transaction);
 
+    if (rpc.Api.isSimulationError(simulated)) {
       return null;
-    } catch (error: any) {
-      console.error('Failed to get asset:', error);
-      return null;
     }
+
+    if (simulated.result) {
+      const assetData = scValToNative(simulated.result.retval);
+      return assetData ? this.mapContractAssetToRWAAsset(assetData) : null;
+    }
+
+    return null;
   }
 
   /**